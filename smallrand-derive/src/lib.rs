@@ -0,0 +1,235 @@
+#![forbid(unsafe_code)]
+
+//! The derive macro for the `ValueFromRng` trait of smallrand.
+//! Enable the `derive` feature of smallrand to use it, rather than using this crate directly.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Expr, Fields,
+    GenericParam, LitInt,
+};
+
+/// Implements `ValueFromRng` for a struct or an enum, so that random values of it
+/// can be generated with `Rng::random`.
+///
+/// Each field is generated with `Rng::random`, unless it has a `#[rng(range = ...)]`
+/// attribute, in which case it is generated with `Rng::range` and the given range.
+/// Each variant of an enum is equally likely, unless variants have a `#[rng(weight = ...)]`
+/// attribute, in which case they are picked with probabilities proportional to the weights.
+/// Variants without the attribute have a weight of 1.
+#[proc_macro_derive(ValueFromRng, attributes(rng))]
+pub fn derive_value_from_rng(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    value_from_rng(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn value_from_rng(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("rng")) {
+        return Err(Error::new(
+            attr.span(),
+            "`rng` attributes can only be used on fields and enum variants",
+        ));
+    }
+    let body = match &input.data {
+        Data::Struct(data) => construct(&quote!(Self), &data.fields)?,
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            let mut total: u64 = 0;
+            for variant in &data.variants {
+                let weight = weight(&variant.attrs)?;
+                if weight == 0 {
+                    continue;
+                }
+                let ident = &variant.ident;
+                let value = construct(&quote!(Self::#ident), &variant.fields)?;
+                let first = total;
+                total = total
+                    .checked_add(weight)
+                    .ok_or_else(|| Error::new(variant.span(), "the total weight is too large"))?;
+                let last = total - 1;
+                arms.push(quote!(#first..=#last => #value,));
+            }
+            if total == 0 {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "an enum must have a variant with a weight above 0",
+                ));
+            }
+            quote! {
+                match ::smallrand::Rng::range(rng, 0..#total) {
+                    #(#arms)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "ValueFromRng cannot be derived for unions",
+            ))
+        }
+    };
+
+    // The type parameters must be possible to generate too
+    for param in &mut input.generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(::smallrand::ValueFromRng));
+        }
+    }
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::smallrand::ValueFromRng for #ident #type_generics #where_clause {
+            #[inline]
+            fn value_from_rng<SmallrandRng: ::smallrand::Rng>(rng: &mut SmallrandRng) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Returns an expression that constructs a struct or an enum variant with random fields.
+fn construct(path: &TokenStream, fields: &Fields) -> Result<TokenStream, Error> {
+    let values = fields
+        .iter()
+        .map(|field| {
+            no_weight(&field.attrs)?;
+            Ok(if let Some(range) = range(&field.attrs)? {
+                quote_spanned!(range.span()=> ::smallrand::Rng::range(rng, #range))
+            } else {
+                quote_spanned!(field.ty.span()=> ::smallrand::Rng::random(rng))
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#values),*)),
+        Fields::Unit => quote!(#path),
+    })
+}
+
+/// Returns the weight of a variant, which is 1 if it has no weight attribute.
+fn weight(attrs: &[Attribute]) -> Result<u64, Error> {
+    let mut weight = 1;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("rng")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("weight") {
+                weight = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `weight`"))
+            }
+        })?;
+    }
+    Ok(weight)
+}
+
+/// Returns the range of a field, if it has a range attribute.
+fn range(attrs: &[Attribute]) -> Result<Option<Expr>, Error> {
+    let mut range = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("rng")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("range") {
+                range = Some(meta.value()?.parse::<Expr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `range`"))
+            }
+        })?;
+    }
+    Ok(range)
+}
+
+/// Returns an error if there is a weight attribute, which is only valid on enum variants.
+fn no_weight(attrs: &[Attribute]) -> Result<(), Error> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("rng")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("weight") {
+                Err(meta.error("`weight` can only be used on enum variants"))
+            } else {
+                // Let the value be parsed by the function that handles it
+                let _: Expr = meta.value()?.parse()?;
+                Ok(())
+            }
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(input: TokenStream) -> String {
+        match value_from_rng(syn::parse2(input).unwrap()) {
+            Ok(output) => output.to_string(),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn struct_fields() {
+        let output = expand(quote! {
+            struct Point<T> {
+                x: T,
+                #[rng(range = 0..10)]
+                y: u32,
+            }
+        });
+        assert!(output.contains("impl < T : :: smallrand :: ValueFromRng >"));
+        assert!(output.contains("x : :: smallrand :: Rng :: random (rng)"));
+        assert!(output.contains("y : :: smallrand :: Rng :: range (rng , 0 .. 10)"));
+    }
+
+    #[test]
+    fn enum_weights() {
+        let output = expand(quote! {
+            enum Shape {
+                #[rng(weight = 3)]
+                Circle(f64),
+                Square,
+                #[rng(weight = 0)]
+                Never,
+            }
+        });
+        assert!(output.contains(":: smallrand :: Rng :: range (rng , 0 .. 4u64)"));
+        assert!(output.contains("0u64 ..= 2u64 => Self :: Circle"));
+        assert!(output.contains("3u64 ..= 3u64 => Self :: Square"));
+        assert!(!output.contains("Never"));
+    }
+
+    #[test]
+    fn invalid_attributes() {
+        assert_eq!(
+            expand(quote! {
+                struct Invalid {
+                    #[rng(weight = 3)]
+                    x: u32,
+                }
+            }),
+            "`weight` can only be used on enum variants"
+        );
+        assert_eq!(
+            expand(quote! {
+                enum Invalid {
+                    #[rng(range = 0..3)]
+                    X,
+                }
+            }),
+            "expected `weight`"
+        );
+        assert_eq!(
+            expand(quote! {
+                enum Empty {}
+            }),
+            "an enum must have a variant with a weight above 0"
+        );
+    }
+}
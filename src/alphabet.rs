@@ -0,0 +1,231 @@
+use crate::rng::Rng;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A set of characters that random strings can be generated from,
+/// with [Rng::string](crate::Rng::string()) and [Rng::fill_chars](crate::Rng::fill_chars()).
+///
+/// Every character of the alphabet is equally likely to be generated.
+/// If the number of characters is a power of two, each character is made from a fixed
+/// number of random bits, which is faster than picking characters from other alphabets.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Alphabet, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let token = rng.string(32, &Alphabet::BASE64URL);
+/// let dna = rng.string(100, &Alphabet::new("ACGT").unwrap());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alphabet {
+    chars: Cow<'static, [char]>,
+}
+
+impl Alphabet {
+    /// The digits and the upper and lower case ASCII letters.
+    pub const ALPHANUMERIC: Self = Self::from_static(&ALPHANUMERIC);
+
+    /// The lower case ASCII letters.
+    pub const LOWERCASE: Self = Self::from_static(&LOWERCASE);
+
+    /// The digits and the lower case letters from a to f.
+    pub const HEX: Self = Self::from_static(&HEX);
+
+    /// The base32 alphabet of RFC 4648, which is the upper case letters and the digits 2 to 7.
+    pub const BASE32: Self = Self::from_static(&BASE32);
+
+    /// The URL and filename safe base64 alphabet of RFC 4648,
+    /// which is the letters, the digits, '-' and '_'.
+    pub const BASE64URL: Self = Self::from_static(&BASE64URL);
+
+    /// The digits and the letters, except '0', 'O', '1' and 'l',
+    /// which are easy to mistake for each other.
+    pub const UNAMBIGUOUS: Self = Self::from_static(&UNAMBIGUOUS);
+
+    const fn from_static(chars: &'static [char]) -> Self {
+        Self {
+            chars: Cow::Borrowed(chars),
+        }
+    }
+
+    /// Creates an alphabet with custom characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars`: The characters of the alphabet
+    ///
+    /// returns: [Alphabet], or an error if there are no characters or if a character is repeated
+    ///
+    /// # Errors
+    ///
+    /// Returns [AlphabetError::NoChars] if `chars` is empty,
+    /// and [AlphabetError::DuplicateChar] if a character occurs more than once.
+    pub fn new(chars: &str) -> Result<Self, AlphabetError> {
+        let chars: Vec<char> = chars.chars().collect();
+        if chars.is_empty() {
+            return Err(AlphabetError::NoChars);
+        }
+        let mut sorted = chars.clone();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(AlphabetError::DuplicateChar);
+        }
+        Ok(Self {
+            chars: Cow::Owned(chars),
+        })
+    }
+
+    /// Returns the characters of the alphabet.
+    #[must_use]
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Generates a number of random characters from the alphabet, and passes them to `output`.
+    pub(crate) fn generate(&self, rng: &mut impl Rng, count: usize, mut output: impl FnMut(char)) {
+        let length = self.chars.len();
+        if length == 1 {
+            (0..count).for_each(|_| output(self.chars[0]));
+        } else if length.is_power_of_two() {
+            // Each character is made from the next bits of a random u64,
+            // so no random values need to be rejected
+            let bits = length.trailing_zeros();
+            let mask = (1_u64 << bits) - 1;
+            let chars_per_u64 = (64 / bits) as usize;
+            let mut remaining = count;
+            while remaining > 0 {
+                let mut random = rng.random_u64();
+                let chars = chars_per_u64.min(remaining);
+                for _ in 0..chars {
+                    #[allow(clippy::cast_possible_truncation)]
+                    output(self.chars[(random & mask) as usize]);
+                    random >>= bits;
+                }
+                remaining -= chars;
+            }
+        } else {
+            (0..count).for_each(|_| output(self.chars[rng.range(0..length)]));
+        }
+    }
+}
+
+const fn ascii_chars<const N: usize>(ascii: &[u8; N]) -> [char; N] {
+    let mut chars = ['\0'; N];
+    let mut inx = 0;
+    while inx < N {
+        chars[inx] = ascii[inx] as char;
+        inx += 1;
+    }
+    chars
+}
+
+const ALPHANUMERIC: [char; 62] =
+    ascii_chars(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+const LOWERCASE: [char; 26] = ascii_chars(b"abcdefghijklmnopqrstuvwxyz");
+const HEX: [char; 16] = ascii_chars(b"0123456789abcdef");
+const BASE32: [char; 32] = ascii_chars(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+const BASE64URL: [char; 64] =
+    ascii_chars(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_");
+const UNAMBIGUOUS: [char; 58] =
+    ascii_chars(b"23456789ABCDEFGHIJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz");
+
+/// The error returned when an [Alphabet] is constructed with invalid characters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AlphabetError {
+    /// The alphabet had no characters.
+    NoChars,
+    /// A character occurred more than once.
+    DuplicateChar,
+}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoChars => "alphabet must have at least one character",
+            Self::DuplicateChar => "alphabet must not have duplicate characters",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AlphabetError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    /// Generates characters from an alphabet,
+    /// and asserts that all of them are equally likely
+    fn assert_uniform(alphabet: &Alphabet, critical_value: f64) {
+        let mut rng = rng();
+        let chars = alphabet.chars();
+        let mut count = vec![0_usize; chars.len()];
+        alphabet.generate(&mut rng, 1000 * chars.len(), |c| {
+            count[chars.iter().position(|a| *a == c).unwrap()] += 1;
+        });
+        let chi_square = chi_square(&count, &vec![1000.0; chars.len()]);
+        assert!(chi_square < critical_value, "{chi_square}");
+    }
+
+    #[test]
+    fn presets_are_uniform() {
+        // The critical values at p = 0.001 for the number of characters minus one
+        assert_uniform(&Alphabet::ALPHANUMERIC, 100.89);
+        assert_uniform(&Alphabet::LOWERCASE, 52.62);
+        assert_uniform(&Alphabet::HEX, 37.70);
+        assert_uniform(&Alphabet::BASE32, 61.10);
+        assert_uniform(&Alphabet::BASE64URL, 103.44);
+        assert_uniform(&Alphabet::UNAMBIGUOUS, 95.75);
+    }
+
+    #[test]
+    fn custom_alphabets_are_uniform() {
+        assert_uniform(&Alphabet::new("ACGT").unwrap(), 16.27);
+        assert_uniform(&Alphabet::new("æøå").unwrap(), 13.82);
+    }
+
+    #[test]
+    fn presets_have_distinct_chars() {
+        for preset in [
+            Alphabet::ALPHANUMERIC,
+            Alphabet::LOWERCASE,
+            Alphabet::HEX,
+            Alphabet::BASE32,
+            Alphabet::BASE64URL,
+            Alphabet::UNAMBIGUOUS,
+        ] {
+            let chars: alloc::string::String = preset.chars().iter().collect();
+            assert_eq!(Alphabet::new(&chars), Ok(preset));
+        }
+        assert!(!Alphabet::UNAMBIGUOUS
+            .chars()
+            .iter()
+            .any(|c| "0O1l".contains(*c)));
+    }
+
+    #[test]
+    fn generates_the_requested_number_of_chars() {
+        let mut rng = rng();
+        for alphabet in [Alphabet::HEX, Alphabet::new("x").unwrap()] {
+            for count in [0, 1, 15, 16, 17, 100] {
+                let mut generated = 0;
+                alphabet.generate(&mut rng, count, |_| generated += 1);
+                assert_eq!(generated, count);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_alphabets() {
+        assert_eq!(Alphabet::new(""), Err(AlphabetError::NoChars));
+        assert_eq!(Alphabet::new("abca"), Err(AlphabetError::DuplicateChar));
+    }
+}
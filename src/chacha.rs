@@ -1,79 +1,246 @@
 #![allow(clippy::inline_always)]
 
-use crate::{nonces, EntropySource, Rng};
+use crate::{nonces, CryptoRng, EntropySource, Rng};
 use core::ops::BitXor;
 
-#[allow(clippy::doc_markdown)]
-/// This is a random generator based on the ChaCha crypto algorithm with 12 rounds.
+// Macro to define the public ChaCha random generators, which only differ in the number of rounds
+macro_rules! chacha_rng {
+    ($(#[$attr: meta])* $name: ident, $rounds: literal) => {
+        $(#[$attr])*
+        #[allow(clippy::module_name_repetitions)]
+        pub struct $name(ChaCha<$rounds>);
+
+        impl $name {
+            #[doc = concat!("Creates a new [", stringify!($name), "] random generator using a seed from an [EntropySource].")]
+            /// The nonce is taken from the nanoseconds part of `SystemTime` when
+            /// building with `std` enabled, to provide an extra safety net in case the random
+            /// entropy source is broken.
+            /// For non-std builds, the nonce is 0 (which is what `rand` always does).
+            ///
+            /// # Arguments
+            ///
+            /// * `entropy_source`: The source of the seed
+            ///
+            #[doc = concat!("returns: [", stringify!($name), "]")]
+            ///
+            #[must_use]
+            pub fn from_entropy<T>(entropy_source: &mut T) -> Self
+            where
+                T: EntropySource,
+            {
+                let mut key = [0; 32];
+                entropy_source.fill(&mut key);
+                Self(ChaCha::<$rounds>::new(&key, nonces::nonce_u64()))
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($name), "] random generator from a specified seed and nonce.")]
+            ///
+            /// # Arguments
+            ///
+            /// * `seed`: The seed (i.e. key) to initialize with
+            /// * `nonce`: The nonce to initialize with
+            ///
+            #[doc = concat!("returns: [", stringify!($name), "]")]
+            ///
+            #[must_use]
+            pub fn from_seed(seed: &[u8; 32], nonce: [u8; 8]) -> Self {
+                Self(ChaCha::<$rounds>::new(seed, nonce))
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($name), "] random generator using a seed from an [EntropySource].")]
+            ///
+            /// # Arguments
+            ///
+            /// * `entropy_source`: The source of the seed
+            /// * `nonce`: The nonce to initialize with
+            ///
+            #[doc = concat!("returns: [", stringify!($name), "]")]
+            ///
+            #[must_use]
+            pub fn from_entropy_and_nonce<T>(entropy_source: &mut T, nonce: [u8; 8]) -> Self
+            where
+                T: EntropySource,
+            {
+                let mut key = [0; 32];
+                entropy_source.fill(&mut key);
+                Self(ChaCha::<$rounds>::new(&key, nonce))
+            }
+
+            /// Selects the stream to generate from, which is the nonce interpreted as a
+            /// little endian u64.
+            /// The word position is kept, so that the next value is generated from the
+            /// same position in the new stream.
+            ///
+            /// # Arguments
+            ///
+            /// * `stream`: The stream to generate from
+            ///
+            #[inline]
+            pub fn set_stream(&mut self, stream: u64) {
+                self.0.set_stream(stream);
+            }
+
+            /// Returns the stream that is being generated from, which is the nonce
+            /// interpreted as a little endian u64.
+            ///
+            /// returns: The current stream
+            ///
+            #[inline]
+            #[must_use]
+            pub fn get_stream(&self) -> u64 {
+                self.0.get_stream()
+            }
+
+            /// Moves to a position in the stream, counted in 32-bit words from its start.
+            /// This takes constant time, and makes it possible to reproduce any part of
+            /// the output without generating everything before it.
+            ///
+            /// # Arguments
+            ///
+            /// * `word_pos`: The number of 32-bit words from the start of the stream
+            ///
+            /// # Panics
+            ///
+            /// Panics if the position is beyond the end of the stream.
+            /// The stream ends before the block with the largest counter value.
+            ///
+            #[inline]
+            pub fn set_word_pos(&mut self, word_pos: u128) {
+                self.0.set_word_pos(word_pos);
+            }
+
+            /// Returns the current position in the stream, counted in 32-bit words from its start.
+            /// A word that has only been partly consumed by `fill_u8` is not counted.
+            ///
+            /// returns: The number of 32-bit words generated from the stream
+            ///
+            #[inline]
+            #[must_use]
+            pub fn get_word_pos(&self) -> u128 {
+                self.0.get_word_pos()
+            }
+        }
+
+        impl Rng for $name {
+            #[inline(always)]
+            fn random_u32(&mut self) -> u32 {
+                self.0.random_u32()
+            }
+
+            #[inline(always)]
+            fn random_u64(&mut self) -> u64 {
+                self.0.random_u64()
+            }
+
+            #[inline(always)]
+            fn fill_u8(&mut self, destination: &mut [u8]) {
+                self.0.fill_u8(destination);
+            }
+        }
+
+        impl CryptoRng for $name {}
+    };
+}
+
+chacha_rng!(
+    /// This is a random generator based on the ChaCha crypto algorithm with 8 rounds.
+    ///
+    /// It is the fastest of the ChaCha generators, and 8 rounds is believed to be sufficient
+    /// ([Too Much Crypto](https://eprint.iacr.org/2019/1492.pdf)),
+    /// but it has the smallest security margin.
+    /// No guarantees of any kind are made that this particular implementation
+    /// is cryptographically secure.
+    ///
+    /// Note that ChaCha is limited to generating 2^64 blocks (2^70 bytes).
+    /// The algorithm will panic if this limit is exceeded.
+    ChaCha8,
+    8
+);
+
+chacha_rng!(
+    /// This is a random generator based on the ChaCha crypto algorithm with 12 rounds.
+    ///
+    /// This algorithm is currently unbroken and can be used to implement
+    /// cryptographically secure random generators, but please note
+    /// that no guarantees of any kind are made that this particular implementation
+    /// is cryptographically secure.
+    ///
+    /// Note that ChaCha is limited to generating 2^64 blocks (2^70 bytes).
+    /// The algorithm will panic if this limit is exceeded.
+    ChaCha12,
+    12
+);
+
+chacha_rng!(
+    /// This is a random generator based on the ChaCha crypto algorithm with 20 rounds,
+    /// which is the number of rounds in the original ChaCha specification.
+    ///
+    /// It is the slowest of the ChaCha generators, but has the largest security margin.
+    /// No guarantees of any kind are made that this particular implementation
+    /// is cryptographically secure.
+    ///
+    /// Note that ChaCha is limited to generating 2^64 blocks (2^70 bytes).
+    /// The algorithm will panic if this limit is exceeded.
+    ChaCha20,
+    20
+);
+
+/// This is a random generator based on the ChaCha crypto algorithm with 12 rounds,
+/// extended to a 192-bit nonce like XChaCha.
 ///
-/// This algorithm is currently unbroken and can be used to implement
-/// cryptographically secure random generators, but please note
-/// that no guarantees of any kind are made that this particular implementation
+/// The key and the first 16 bytes of the nonce are used to derive a subkey with HChaCha,
+/// which is then used as the key of a [ChaCha12] with the last 8 bytes of the nonce.
+/// The nonce is large enough to be chosen at random, so that many independent generators
+/// can be seeded from the same key without worrying about nonce collisions.
+/// No guarantees of any kind are made that this particular implementation
 /// is cryptographically secure.
 ///
 /// Note that ChaCha is limited to generating 2^64 blocks (2^70 bytes).
 /// The algorithm will panic if this limit is exceeded.
 #[allow(clippy::module_name_repetitions)]
-pub struct ChaCha12(ChaCha<12>);
-
-impl ChaCha12 {
-    /// Creates a new [ChaCha12] random generator using a seed from an [EntropySource].
-    /// The nonce is taken from the nanoseconds part of `SystemTime` when
-    /// building with `std` enabled, to provide an extra safety net in case the random
-    /// entropy source is broken.
-    /// For non-std builds, the nonce is 0 (which is what `rand` always does).
-    ///
-    /// # Arguments
-    ///
-    /// * `entropy_source`: The source of the seed
-    ///
-    /// returns: [ChaCha12]
-    ///
-    #[must_use]
-    pub fn from_entropy<T>(entropy_source: &mut T) -> Self
-    where
-        T: EntropySource,
-    {
-        let mut key = [0; 32];
-        entropy_source.fill(&mut key);
-        Self(ChaCha::<12>::new(&key, nonces::nonce_u64()))
-    }
+pub struct XChaCha12(ChaCha<12>);
 
-    /// Creates a new [ChaCha12] random generator from a specified seed and nonce.
+impl XChaCha12 {
+    /// Creates a new [XChaCha12] random generator from a specified seed and extended nonce.
     ///
     /// # Arguments
     ///
     /// * `seed`: The seed (i.e. key) to initialize with
-    /// * `nonce`: The nonce to initialize with
+    /// * `nonce`: The 192-bit nonce to initialize with
     ///
-    /// returns: [ChaCha12]
+    /// returns: [XChaCha12]
     ///
     #[must_use]
-    pub fn from_seed(seed: &[u8; 32], nonce: [u8; 8]) -> Self {
-        Self(ChaCha::<12>::new(seed, nonce))
+    pub fn from_seed(seed: &[u8; 32], nonce: [u8; 24]) -> Self {
+        // The unwraps below cannot fail and will get optimized away
+        let subkey = ChaCha::<12>::hchacha(seed, nonce[0..16].try_into().unwrap());
+        Self(ChaCha::<12>::new(
+            &subkey,
+            nonce[16..24].try_into().unwrap(),
+        ))
     }
 
-    /// Creates a new [ChaCha12] random generator using a seed from an [EntropySource].
+    /// Creates a new [XChaCha12] random generator using a seed from an [EntropySource].
     ///
     /// # Arguments
     ///
     /// * `entropy_source`: The source of the seed
-    /// * `nonce`: The nonce to initialize with
+    /// * `nonce`: The 192-bit nonce to initialize with
     ///
-    /// returns: [ChaCha12]
+    /// returns: [XChaCha12]
     ///
     #[must_use]
-    pub fn from_entropy_and_nonce<T>(entropy_source: &mut T, nonce: [u8; 8]) -> Self
+    pub fn from_entropy_and_nonce<T>(entropy_source: &mut T, nonce: [u8; 24]) -> Self
     where
         T: EntropySource,
     {
         let mut key = [0; 32];
         entropy_source.fill(&mut key);
-        Self(ChaCha::<12>::new(&key, nonce))
+        Self::from_seed(&key, nonce)
     }
 }
 
-impl Rng for ChaCha12 {
+impl Rng for XChaCha12 {
     #[inline(always)]
     fn random_u32(&mut self) -> u32 {
         self.0.random_u32()
@@ -90,110 +257,271 @@ impl Rng for ChaCha12 {
     }
 }
 
-struct ChaCha<const ROUNDS: usize> {
+impl CryptoRng for XChaCha12 {}
+
+/// The number of blocks that are generated at a time.
+const BLOCKS: usize = 4;
+
+/// The number of bytes in a block.
+const BLOCK_SIZE: usize = 64;
+
+pub(crate) struct ChaCha<const ROUNDS: usize> {
     state: [u32; 16],
     inx: usize,
-    buffer: [u8; 64],
+    buffer: [u8; BLOCK_SIZE * BLOCKS],
+}
+
+/// Creates the ChaCha state for a key, with the counter and nonce words given by `counter_and_nonce`.
+pub(crate) fn initial_state(key: &[u8; 32], counter_and_nonce: [u32; 4]) -> [u32; 16] {
+    const SIGMA: &[u8; 16] = b"expand 32-byte k";
+    // The unwraps below cannot fail and will get optimized away
+    [
+        u32::from_le_bytes(SIGMA[0..4].try_into().unwrap()),
+        u32::from_le_bytes(SIGMA[4..8].try_into().unwrap()),
+        u32::from_le_bytes(SIGMA[8..12].try_into().unwrap()),
+        u32::from_le_bytes(SIGMA[12..16].try_into().unwrap()),
+        u32::from_le_bytes(key[0..4].try_into().unwrap()),
+        u32::from_le_bytes(key[4..8].try_into().unwrap()),
+        u32::from_le_bytes(key[8..12].try_into().unwrap()),
+        u32::from_le_bytes(key[12..16].try_into().unwrap()),
+        u32::from_le_bytes(key[16..20].try_into().unwrap()),
+        u32::from_le_bytes(key[20..24].try_into().unwrap()),
+        u32::from_le_bytes(key[24..28].try_into().unwrap()),
+        u32::from_le_bytes(key[28..32].try_into().unwrap()),
+        counter_and_nonce[0],
+        counter_and_nonce[1],
+        counter_and_nonce[2],
+        counter_and_nonce[3],
+    ]
 }
 
 impl<const ROUNDS: usize> ChaCha<ROUNDS> {
     fn new(key: &[u8; 32], nonce: [u8; 8]) -> Self {
-        const SIGMA: &[u8; 16] = b"expand 32-byte k";
         // The unwraps below cannot fail and will get optimized away
         let mut s = Self {
-            state: [
-                u32::from_le_bytes(SIGMA[0..4].try_into().unwrap()),
-                u32::from_le_bytes(SIGMA[4..8].try_into().unwrap()),
-                u32::from_le_bytes(SIGMA[8..12].try_into().unwrap()),
-                u32::from_le_bytes(SIGMA[12..16].try_into().unwrap()),
-                u32::from_le_bytes(key[0..4].try_into().unwrap()),
-                u32::from_le_bytes(key[4..8].try_into().unwrap()),
-                u32::from_le_bytes(key[8..12].try_into().unwrap()),
-                u32::from_le_bytes(key[12..16].try_into().unwrap()),
-                u32::from_le_bytes(key[16..20].try_into().unwrap()),
-                u32::from_le_bytes(key[20..24].try_into().unwrap()),
-                u32::from_le_bytes(key[24..28].try_into().unwrap()),
-                u32::from_le_bytes(key[28..32].try_into().unwrap()),
-                0,
-                0,
-                u32::from_le_bytes(nonce[0..4].try_into().unwrap()),
-                u32::from_le_bytes(nonce[4..8].try_into().unwrap()),
-            ],
+            state: initial_state(
+                key,
+                [
+                    0,
+                    0,
+                    u32::from_le_bytes(nonce[0..4].try_into().unwrap()),
+                    u32::from_le_bytes(nonce[4..8].try_into().unwrap()),
+                ],
+            ),
             inx: 0,
-            buffer: [0; 64],
+            buffer: [0; BLOCK_SIZE * BLOCKS],
         };
-        s.generate_block();
+        s.generate_blocks();
         s
     }
 
-    fn generate_block(&mut self) {
-        let mut x = [0_u32; 16];
-        x.copy_from_slice(&self.state);
-        for _round in (0..ROUNDS).step_by(2) {
-            // Odd round
-            Self::quarter_round(&mut x, 0, 4, 8, 12);
-            Self::quarter_round(&mut x, 1, 5, 9, 13);
-            Self::quarter_round(&mut x, 2, 6, 10, 14);
-            Self::quarter_round(&mut x, 3, 7, 11, 15);
+    const WORDS_PER_BLOCK: u128 = 16;
 
-            // Even round
-            Self::quarter_round(&mut x, 0, 5, 10, 15);
-            Self::quarter_round(&mut x, 1, 6, 11, 12);
-            Self::quarter_round(&mut x, 2, 7, 8, 13);
-            Self::quarter_round(&mut x, 3, 4, 9, 14);
+    fn set_stream(&mut self, stream: u64) {
+        let word_pos = self.get_word_pos();
+        let [low, high] = split_u64(stream);
+        self.state[14] = low;
+        self.state[15] = high;
+        self.set_word_pos(word_pos);
+    }
+
+    fn get_stream(&self) -> u64 {
+        join_u64(self.state[14], self.state[15])
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn set_word_pos(&mut self, word_pos: u128) {
+        let block =
+            u64::try_from(word_pos / Self::WORDS_PER_BLOCK).expect("Word position out of range");
+        let [low, high] = split_u64(block);
+        self.state[12] = low;
+        self.state[13] = high;
+        self.generate_blocks();
+        self.inx += (word_pos % Self::WORDS_PER_BLOCK) as usize * core::mem::size_of::<u32>();
+    }
+
+    fn get_word_pos(&self) -> u128 {
+        // The counter has already been advanced past the blocks in the buffer
+        let block = join_u64(self.state[12], self.state[13]).wrapping_sub(BLOCKS as u64);
+        u128::from(block) * Self::WORDS_PER_BLOCK + (self.inx / core::mem::size_of::<u32>()) as u128
+    }
+
+    /// Fills the buffer with the next blocks, and advances the counter past them.
+    /// The blocks are computed together with their words interleaved,
+    /// so that `x[i][n]` is word `i` of block `n`.
+    /// This lets the compiler operate on the same word of all blocks with vector instructions.
+    fn generate_blocks(&mut self) {
+        let counter = join_u64(self.state[12], self.state[13]);
+        // The last block counter is never used. Near the end of the stream fewer than BLOCKS
+        // blocks remain, and these are put at the end of the buffer to keep the word position right.
+        let blocks = (u64::MAX - counter).min(BLOCKS as u64) as usize;
+        assert!(blocks > 0, "Max number of blocks exceeded");
+        let mut initial = self.state.map(|word| [word; BLOCKS]);
+        let counters: [[u32; 2]; BLOCKS] =
+            core::array::from_fn(|block| split_u64(counter.wrapping_add(block as u64)));
+        initial[12] = counters.map(|[low, _]| low);
+        initial[13] = counters.map(|[_, high]| high);
+        let mut x = initial;
+        Self::apply_rounds(&mut x);
+        for (words, initial_words) in x.iter_mut().zip(initial) {
+            for (word, initial_word) in words.iter_mut().zip(initial_words) {
+                *word = word.wrapping_add(initial_word);
+            }
+        }
+        let first = BLOCKS - blocks;
+        for (block, output) in self
+            .buffer
+            .chunks_exact_mut(BLOCK_SIZE)
+            .skip(first)
+            .enumerate()
+        {
+            for (bytes, words) in output.chunks_exact_mut(core::mem::size_of::<u32>()).zip(&x) {
+                bytes.copy_from_slice(&words[block].to_le_bytes());
+            }
         }
+        [self.state[12], self.state[13]] = split_u64(counter + blocks as u64);
+        self.inx = first * BLOCK_SIZE;
+    }
+
+    /// Derives a subkey from a key and the first 16 bytes of an extended nonce,
+    /// using the HChaCha function from the XChaCha draft.
+    fn hchacha(key: &[u8; 32], nonce: &[u8; 16]) -> [u8; 32] {
+        // The unwraps below cannot fail and will get optimized away
+        let state = initial_state(
+            key,
+            [
+                u32::from_le_bytes(nonce[0..4].try_into().unwrap()),
+                u32::from_le_bytes(nonce[4..8].try_into().unwrap()),
+                u32::from_le_bytes(nonce[8..12].try_into().unwrap()),
+                u32::from_le_bytes(nonce[12..16].try_into().unwrap()),
+            ],
+        );
+        let mut x = state.map(|word| [word]);
+        Self::apply_rounds(&mut x);
+        let x = x.map(|[word]| word);
+        let mut subkey = [0; 32];
+        let words = x[0..4].iter().chain(&x[12..16]);
+        for (bytes, word) in subkey
+            .chunks_exact_mut(core::mem::size_of::<u32>())
+            .zip(words)
+        {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        subkey
+    }
+
+    /// Returns the index of the next `size` bytes of the buffer, and moves past them.
+    /// A value never spans two blocks: the rest of the block is skipped if the value
+    /// does not fit in it, so that the output is the same as when one block
+    /// was generated at a time.
+    #[inline(always)]
+    fn next_value_index(&mut self, size: usize) -> usize {
+        if self.inx % BLOCK_SIZE + size > BLOCK_SIZE {
+            self.inx = self.inx.next_multiple_of(BLOCK_SIZE);
+        }
+        if self.inx == self.buffer.len() {
+            self.generate_blocks();
+        }
+        let inx = self.inx;
+        self.inx += size;
+        inx
+    }
+
+    /// Computes the output block for a state, without changing the state.
+    pub(crate) fn compute_block(state: &[u32; 16], buffer: &mut [u8; 64]) {
+        let mut x = state.map(|word| [word]);
+        Self::apply_rounds(&mut x);
+        let mut x = x.map(|[word]| word);
         for (i, element) in x.iter_mut().enumerate() {
-            *element = element.wrapping_add(self.state[i]);
+            *element = element.wrapping_add(state[i]);
         }
-        let mut blocks = self.buffer.chunks_exact_mut(core::mem::size_of::<u32>());
+        let mut blocks = buffer.chunks_exact_mut(core::mem::size_of::<u32>());
         for (i, block) in blocks.by_ref().enumerate() {
             block.copy_from_slice(&x[i].to_le_bytes());
         }
-        self.state[12] = self.state[12].wrapping_add(1);
-        if self.state[12] == 0 {
-            self.state[13] = self.state[13]
-                .checked_add(1)
-                .expect("Max number of blocks exceeded");
+    }
+
+    /// Applies the ChaCha rounds to one or more interleaved states,
+    /// where `x[i][n]` is word `i` of state `n`.
+    #[inline(always)]
+    fn apply_rounds<const N: usize>(x: &mut [[u32; N]; 16]) {
+        for _round in (0..ROUNDS).step_by(2) {
+            // Odd round
+            Self::quarter_round(x, 0, 4, 8, 12);
+            Self::quarter_round(x, 1, 5, 9, 13);
+            Self::quarter_round(x, 2, 6, 10, 14);
+            Self::quarter_round(x, 3, 7, 11, 15);
+
+            // Even round
+            Self::quarter_round(x, 0, 5, 10, 15);
+            Self::quarter_round(x, 1, 6, 11, 12);
+            Self::quarter_round(x, 2, 7, 8, 13);
+            Self::quarter_round(x, 3, 4, 9, 14);
         }
     }
 
     #[allow(clippy::many_single_char_names)]
     #[inline(always)]
-    fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
-        x[a] = x[a].wrapping_add(x[b]);
-        x[d] = x[d].bitxor(x[a]).rotate_left(16);
-        x[c] = x[c].wrapping_add(x[d]);
-        x[b] = x[b].bitxor(x[c]).rotate_left(12);
-        x[a] = x[a].wrapping_add(x[b]);
-        x[d] = x[d].bitxor(x[a]).rotate_left(8);
-        x[c] = x[c].wrapping_add(x[d]);
-        x[b] = x[b].bitxor(x[c]).rotate_left(7);
+    fn quarter_round<const N: usize>(
+        x: &mut [[u32; N]; 16],
+        a: usize,
+        b: usize,
+        c: usize,
+        d: usize,
+    ) {
+        Self::add(x, a, b);
+        Self::xor_rotate(x, d, a, 16);
+        Self::add(x, c, d);
+        Self::xor_rotate(x, b, c, 12);
+        Self::add(x, a, b);
+        Self::xor_rotate(x, d, a, 8);
+        Self::add(x, c, d);
+        Self::xor_rotate(x, b, c, 7);
+    }
+
+    /// Adds word `from` to word `to`, in all the interleaved states.
+    #[inline(always)]
+    fn add<const N: usize>(x: &mut [[u32; N]; 16], to: usize, from: usize) {
+        let from = x[from];
+        for (word, from) in x[to].iter_mut().zip(from) {
+            *word = word.wrapping_add(from);
+        }
+    }
+
+    /// XORs word `from` into word `to` and rotates it left, in all the interleaved states.
+    #[inline(always)]
+    fn xor_rotate<const N: usize>(x: &mut [[u32; N]; 16], to: usize, from: usize, bits: u32) {
+        let from = x[from];
+        for (word, from) in x[to].iter_mut().zip(from) {
+            *word = word.bitxor(from).rotate_left(bits);
+        }
     }
 }
 
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+fn split_u64(value: u64) -> [u32; 2] {
+    [value as u32, (value >> 32) as u32]
+}
+
+#[inline]
+fn join_u64(low: u32, high: u32) -> u64 {
+    u64::from(low) | (u64::from(high) << 32)
+}
+
 impl<const ROUNDS: usize> Rng for ChaCha<ROUNDS> {
     #[inline]
     fn random_u32(&mut self) -> u32 {
         const SIZE: usize = core::mem::size_of::<u32>();
-        if self.inx + SIZE > self.buffer.len() {
-            self.generate_block();
-            self.inx = 0;
-        }
-        let value = u32::from_le_bytes(self.buffer[self.inx..self.inx + SIZE].try_into().unwrap());
-        self.inx += SIZE;
-        value
+        let inx = self.next_value_index(SIZE);
+        u32::from_le_bytes(self.buffer[inx..inx + SIZE].try_into().unwrap())
     }
 
     #[inline]
     fn random_u64(&mut self) -> u64 {
         const SIZE: usize = core::mem::size_of::<u64>();
-        if self.inx + SIZE > self.buffer.len() {
-            self.generate_block();
-            self.inx = 0;
-        }
-        let value = u64::from_le_bytes(self.buffer[self.inx..self.inx + SIZE].try_into().unwrap());
-        self.inx += SIZE;
-        value
+        let inx = self.next_value_index(SIZE);
+        u64::from_le_bytes(self.buffer[inx..inx + SIZE].try_into().unwrap())
     }
 
     #[inline]
@@ -204,8 +532,7 @@ impl<const ROUNDS: usize> Rng for ChaCha<ROUNDS> {
         let mut out_inx: usize = 0;
         while out_inx < destination.len() {
             if self.inx == self.buffer.len() {
-                self.generate_block();
-                self.inx = 0;
+                self.generate_blocks();
             }
             let to_copy = usize::min(self.buffer.len() - self.inx, destination.len() - out_inx);
             debug_assert!(to_copy > 0);
@@ -513,6 +840,247 @@ mod test {
         );
     }
 
+    #[test]
+    fn public_generators_have_the_right_number_of_rounds() {
+        fn first_block(rng: &mut impl Rng) -> [u8; 64] {
+            let mut output = [0u8; 64];
+            rng.fill_u8(&mut output);
+            output
+        }
+
+        let key = [0x55; 32];
+        let nonce = [0xaa; 8];
+        assert_eq!(
+            first_block(&mut ChaCha8::from_seed(&key, nonce)),
+            first_block(&mut ChaCha::<8>::new(&key, nonce))
+        );
+        assert_eq!(
+            first_block(&mut ChaCha12::from_seed(&key, nonce)),
+            first_block(&mut ChaCha::<12>::new(&key, nonce))
+        );
+        assert_eq!(
+            first_block(&mut ChaCha20::from_seed(&key, nonce)),
+            first_block(&mut ChaCha::<20>::new(&key, nonce))
+        );
+        assert_ne!(
+            first_block(&mut ChaCha8::from_seed(&key, nonce)),
+            first_block(&mut ChaCha20::from_seed(&key, nonce))
+        );
+    }
+
+    #[test]
+    fn blocks_are_the_same_as_when_generated_one_at_a_time() {
+        let mut rng = ChaCha::<12>::new(&[0x55; 32], [0xaa; 8]);
+        let mut state = initial_state(&[0x55; 32], [0, 0, 0xaaaa_aaaa, 0xaaaa_aaaa]);
+        let mut expected = [0u8; 64];
+        for _ in 0..3 * BLOCKS {
+            let mut output = [0u8; 64];
+            rng.fill_u8(&mut output);
+            ChaCha::<12>::compute_block(&state, &mut expected);
+            assert_eq!(output, expected);
+            state[12] += 1;
+        }
+    }
+
+    /// The generator as it was when one block was generated at a time,
+    /// to check that generating several blocks at a time does not change the output.
+    struct OneBlockChaCha {
+        state: [u32; 16],
+        inx: usize,
+        buffer: [u8; 64],
+    }
+
+    impl OneBlockChaCha {
+        fn new(key: &[u8; 32], nonce: [u8; 8]) -> Self {
+            Self {
+                state: initial_state(
+                    key,
+                    [
+                        0,
+                        0,
+                        u32::from_le_bytes(nonce[0..4].try_into().unwrap()),
+                        u32::from_le_bytes(nonce[4..8].try_into().unwrap()),
+                    ],
+                ),
+                inx: 64,
+                buffer: [0; 64],
+            }
+        }
+
+        fn generate_block(&mut self) {
+            ChaCha::<12>::compute_block(&self.state, &mut self.buffer);
+            self.state[12] += 1;
+            self.inx = 0;
+        }
+
+        fn next_bytes<const SIZE: usize>(&mut self) -> [u8; SIZE] {
+            if self.inx + SIZE > self.buffer.len() {
+                self.generate_block();
+            }
+            let bytes = self.buffer[self.inx..self.inx + SIZE].try_into().unwrap();
+            self.inx += SIZE;
+            bytes
+        }
+
+        fn fill_u8(&mut self, destination: &mut [u8]) {
+            for byte in destination {
+                if self.inx == self.buffer.len() {
+                    self.generate_block();
+                }
+                *byte = self.buffer[self.inx];
+                self.inx += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn mixed_output_is_the_same_as_when_generated_one_block_at_a_time() {
+        let key = [0x55; 32];
+        let nonce = [0xaa; 8];
+        let mut rng = ChaCha12::from_seed(&key, nonce);
+        let mut expected = OneBlockChaCha::new(&key, nonce);
+        for i in 0..1000 {
+            match i % 5 {
+                0 | 3 => assert_eq!(rng.random_u64(), u64::from_le_bytes(expected.next_bytes())),
+                1 | 4 => assert_eq!(rng.random_u32(), u32::from_le_bytes(expected.next_bytes())),
+                _ => {
+                    let mut output = [0u8; 11];
+                    let mut expected_output = [0u8; 11];
+                    let length = i % 11;
+                    rng.fill_u8(&mut output[..length]);
+                    expected.fill_u8(&mut expected_output[..length]);
+                    assert_eq!(output, expected_output);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn set_word_pos_is_the_same_as_generating_up_to_it() {
+        let mut rng = ChaCha12::from_seed(&[0x55; 32], [0xaa; 8]);
+        let output: [u32; 100] = core::array::from_fn(|_| rng.random_u32());
+        assert_eq!(rng.get_word_pos(), 100);
+        for word_pos in [0, 1, 15, 16, 17, 63, 64, 99] {
+            let mut rng = ChaCha12::from_seed(&[0x55; 32], [0xaa; 8]);
+            rng.set_word_pos(word_pos);
+            assert_eq!(rng.get_word_pos(), word_pos);
+            assert_eq!(rng.random_u32(), output[word_pos as usize]);
+            assert_eq!(rng.get_word_pos(), word_pos + 1);
+        }
+    }
+
+    #[test]
+    fn word_pos_after_bytes() {
+        let mut rng = ChaCha12::from_seed(&[0; 32], [0; 8]);
+        assert_eq!(rng.get_word_pos(), 0);
+        rng.fill_u8(&mut [0; 7]);
+        assert_eq!(rng.get_word_pos(), 1);
+        rng.fill_u8(&mut [0; 57]);
+        assert_eq!(rng.get_word_pos(), 16);
+        let _ = rng.random_u64();
+        assert_eq!(rng.get_word_pos(), 18);
+    }
+
+    #[test]
+    fn word_pos_at_end_of_stream() {
+        let mut rng = ChaCha12::from_seed(&[0; 32], [0; 8]);
+        let word_pos = (u128::from(u64::MAX) - 1) * 16 + 3;
+        rng.set_word_pos(word_pos);
+        assert_eq!(rng.get_word_pos(), word_pos);
+    }
+
+    #[test]
+    fn last_blocks_of_stream() {
+        let mut rng = ChaCha12::from_seed(&[0x55; 32], [0xaa; 8]);
+        let mut state = initial_state(&[0x55; 32], [0, 0, 0xaaaa_aaaa, 0xaaaa_aaaa]);
+        let mut expected = [0; 64];
+        for last_block in 1..=BLOCKS as u64 + 1 {
+            let block = u64::MAX - last_block;
+            rng.set_word_pos(u128::from(block) * 16);
+            let mut output = [0; 64];
+            for remaining in (0..last_block).rev() {
+                [state[12], state[13]] = split_u64(u64::MAX - 1 - remaining);
+                ChaCha::<12>::compute_block(&state, &mut expected);
+                rng.fill_u8(&mut output);
+                assert_eq!(output, expected);
+            }
+            assert_eq!(rng.get_word_pos(), u128::from(u64::MAX) * 16);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Max number of blocks exceeded")]
+    fn reading_beyond_end_of_stream_panics() {
+        let mut rng = ChaCha12::from_seed(&[0; 32], [0; 8]);
+        rng.set_word_pos(u128::from(u64::MAX - 1) * 16 + 15);
+        rng.random_u32();
+        rng.random_u32();
+    }
+
+    #[test]
+    #[should_panic(expected = "Word position out of range")]
+    fn word_pos_beyond_end_of_stream_panics() {
+        let mut rng = ChaCha12::from_seed(&[0; 32], [0; 8]);
+        rng.set_word_pos(1 << 68);
+    }
+
+    #[test]
+    fn set_stream_is_the_same_as_changing_nonce() {
+        let mut expected = ChaCha12::from_seed(&[0x55; 32], [1, 2, 3, 4, 5, 6, 7, 8]);
+        expected.set_word_pos(40);
+        let mut rng = ChaCha12::from_seed(&[0x55; 32], [0; 8]);
+        assert_eq!(rng.get_stream(), 0);
+        rng.set_word_pos(40);
+        rng.set_stream(0x0807_0605_0403_0201);
+        assert_eq!(rng.get_stream(), 0x0807_0605_0403_0201);
+        assert_eq!(rng.get_word_pos(), 40);
+        assert_eq!(rng.random_u64(), expected.random_u64());
+    }
+
+    #[test]
+    fn hchacha20() {
+        // This is the HChaCha20 test vector from section 2.2.1 of the XChaCha draft
+        // (https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03)
+        let subkey = ChaCha::<20>::hchacha(
+            &[
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+                0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+                0x1c, 0x1d, 0x1e, 0x1f,
+            ],
+            &[
+                0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00, 0x31, 0x41,
+                0x59, 0x27,
+            ],
+        );
+
+        assert_eq!(
+            subkey,
+            [
+                0x82, 0x41, 0x3b, 0x42, 0x27, 0xb2, 0x7b, 0xfe, 0xd3, 0x0e, 0x42, 0x50, 0x8a, 0x87,
+                0x7d, 0x73, 0xa0, 0xf9, 0xe4, 0xd5, 0x8a, 0x74, 0xa8, 0x53, 0xc1, 0x2e, 0xc4, 0x13,
+                0x26, 0xd3, 0xec, 0xdc,
+            ]
+        );
+    }
+
+    #[test]
+    fn xchacha12_uses_the_hchacha_subkey() {
+        let key = [0x55; 32];
+        let nonce: [u8; 24] = core::array::from_fn(|i| i as u8);
+        let subkey = ChaCha::<12>::hchacha(&key, nonce[0..16].try_into().unwrap());
+
+        let mut output = [0u8; 128];
+        XChaCha12::from_seed(&key, nonce).fill_u8(&mut output);
+        let mut expected = [0u8; 128];
+        ChaCha12::from_seed(&subkey, nonce[16..24].try_into().unwrap()).fill_u8(&mut expected);
+        assert_eq!(output, expected);
+
+        let mut other_nonce = nonce;
+        other_nonce[0] ^= 1;
+        XChaCha12::from_seed(&key, other_nonce).fill_u8(&mut expected);
+        assert_ne!(output, expected);
+    }
+
     #[test]
     fn chacha8_byte_output() {
         // This test uses the same test vector as the Chacha crate, to verify against
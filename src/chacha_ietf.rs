@@ -0,0 +1,311 @@
+use crate::chacha::{initial_state, ChaCha};
+use crate::{nonces, CryptoRng, EntropySource, Rng};
+use core::fmt;
+
+/// This is a random generator based on the IETF variant of ChaCha20 from RFC 8439,
+/// which has a 96-bit nonce and a 32-bit block counter.
+///
+/// Its output is the same as the RFC 8439 key stream, which makes it possible to reproduce
+/// random streams derived by other systems that use RFC 8439 ChaCha20.
+/// If you do not need this, then use [ChaCha20](crate::ChaCha20) instead,
+/// as it has a 64-bit block counter.
+///
+/// Note that the 32-bit block counter limits the output to 2^32 blocks (256 GiB),
+/// starting from the initial block counter.
+/// [ChaCha20Ietf::try_fill_u8] returns an error if this limit would be exceeded,
+/// and the functions of [Rng] panic.
+pub struct ChaCha20Ietf {
+    state: [u32; 16],
+    inx: usize,
+    buffer: [u8; 64],
+    // Set when the block for the last counter value has been generated
+    counter_wrapped: bool,
+}
+
+impl ChaCha20Ietf {
+    /// Creates a new [ChaCha20Ietf] random generator using a seed from an [EntropySource].
+    /// The first 8 bytes of the nonce are taken from the nanoseconds part of `SystemTime` when
+    /// building with `std` enabled, to provide an extra safety net in case the random
+    /// entropy source is broken.
+    /// The block counter starts at 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy_source`: The source of the seed
+    ///
+    /// returns: [ChaCha20Ietf]
+    ///
+    #[must_use]
+    pub fn from_entropy<T>(entropy_source: &mut T) -> Self
+    where
+        T: EntropySource,
+    {
+        let mut nonce = [0; 12];
+        nonce[0..8].copy_from_slice(&nonces::nonce_u64());
+        Self::from_entropy_and_nonce(entropy_source, nonce)
+    }
+
+    /// Creates a new [ChaCha20Ietf] random generator from a specified seed and nonce.
+    /// The block counter starts at 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed`: The seed (i.e. key) to initialize with
+    /// * `nonce`: The nonce to initialize with
+    ///
+    /// returns: [ChaCha20Ietf]
+    ///
+    #[must_use]
+    pub fn from_seed(seed: &[u8; 32], nonce: [u8; 12]) -> Self {
+        // The unwraps below cannot fail and will get optimized away
+        Self {
+            state: initial_state(
+                seed,
+                [
+                    0,
+                    u32::from_le_bytes(nonce[0..4].try_into().unwrap()),
+                    u32::from_le_bytes(nonce[4..8].try_into().unwrap()),
+                    u32::from_le_bytes(nonce[8..12].try_into().unwrap()),
+                ],
+            ),
+            inx: 64,
+            buffer: [0; 64],
+            counter_wrapped: false,
+        }
+    }
+
+    /// Creates a new [ChaCha20Ietf] random generator using a seed from an [EntropySource].
+    /// The block counter starts at 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy_source`: The source of the seed
+    /// * `nonce`: The nonce to initialize with
+    ///
+    /// returns: [ChaCha20Ietf]
+    ///
+    #[must_use]
+    pub fn from_entropy_and_nonce<T>(entropy_source: &mut T, nonce: [u8; 12]) -> Self
+    where
+        T: EntropySource,
+    {
+        let mut key = [0; 32];
+        entropy_source.fill(&mut key);
+        Self::from_seed(&key, nonce)
+    }
+
+    /// Sets the block counter, so that the next value is generated from the start of that block.
+    /// RFC 8439 uses an initial block counter of 1 when encrypting.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter`: The block counter of the next block to generate
+    ///
+    pub fn set_block_counter(&mut self, counter: u32) {
+        self.state[12] = counter;
+        self.inx = self.buffer.len();
+        self.counter_wrapped = false;
+    }
+
+    /// Fills a slice with random bytes, or returns an error if the 32-bit block counter
+    /// would wrap before the slice has been filled.
+    /// Nothing is generated if an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination`: The slice to fill
+    ///
+    /// # Errors
+    ///
+    /// Returns [CounterOverflowError] if the key stream does not have enough bytes left.
+    ///
+    pub fn try_fill_u8(&mut self, destination: &mut [u8]) -> Result<(), CounterOverflowError> {
+        if destination.len() as u64 > self.bytes_left() {
+            return Err(CounterOverflowError);
+        }
+        let mut out_inx: usize = 0;
+        while out_inx < destination.len() {
+            if self.inx == self.buffer.len() {
+                self.generate_block();
+            }
+            let to_copy = usize::min(self.buffer.len() - self.inx, destination.len() - out_inx);
+            destination[out_inx..(out_inx + to_copy)]
+                .copy_from_slice(&self.buffer[self.inx..(self.inx + to_copy)]);
+            out_inx += to_copy;
+            self.inx += to_copy;
+        }
+        Ok(())
+    }
+
+    fn bytes_left(&self) -> u64 {
+        let blocks_left = if self.counter_wrapped {
+            0
+        } else {
+            (1 << 32) - u64::from(self.state[12])
+        };
+        blocks_left * self.buffer.len() as u64 + (self.buffer.len() - self.inx) as u64
+    }
+
+    fn generate_block(&mut self) {
+        ChaCha::<20>::compute_block(&self.state, &mut self.buffer);
+        self.inx = 0;
+        self.state[12] = self.state[12].wrapping_add(1);
+        self.counter_wrapped = self.state[12] == 0;
+    }
+}
+
+impl Rng for ChaCha20Ietf {
+    #[inline]
+    fn random_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_u8(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    #[inline]
+    fn random_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.fill_u8(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    #[inline]
+    fn fill_u8(&mut self, destination: &mut [u8]) {
+        self.try_fill_u8(destination)
+            .expect("Max number of blocks exceeded");
+    }
+}
+
+impl CryptoRng for ChaCha20Ietf {}
+
+/// The error returned when the 32-bit block counter of a [ChaCha20Ietf] would wrap.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CounterOverflowError;
+
+impl fmt::Display for CounterOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the 32-bit ChaCha block counter would wrap")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CounterOverflowError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RFC_KEY: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d,
+        0x1e, 0x1f,
+    ];
+
+    #[test]
+    fn rfc8439_block_function() {
+        // This is the test vector from section 2.3.2 of RFC 8439
+        let mut rng = ChaCha20Ietf::from_seed(
+            &RFC_KEY,
+            [
+                0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00,
+            ],
+        );
+        rng.set_block_counter(1);
+
+        let mut output = [0u8; 64];
+        rng.fill_u8(&mut output);
+
+        assert_eq!(
+            output,
+            [
+                0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20,
+                0x71, 0xc4, 0xc7, 0xd1, 0xf4, 0xc7, 0x33, 0xc0, 0x68, 0x03, 0x04, 0x22, 0xaa, 0x9a,
+                0xc3, 0xd4, 0x6c, 0x4e, 0xd2, 0x82, 0x64, 0x46, 0x07, 0x9f, 0xaa, 0x09, 0x14, 0xc2,
+                0xd7, 0x05, 0xd9, 0x8b, 0x02, 0xa2, 0xb5, 0x12, 0x9c, 0xd1, 0xde, 0x16, 0x4e, 0xb9,
+                0xcb, 0xd0, 0x83, 0xe8, 0xa2, 0x50, 0x3c, 0x4e,
+            ]
+        );
+    }
+
+    #[test]
+    fn rfc8439_encryption() {
+        // This is the test vector from section 2.4.2 of RFC 8439,
+        // where the key stream is XORed with the plaintext
+        let mut rng = ChaCha20Ietf::from_seed(
+            &RFC_KEY,
+            [
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00,
+            ],
+        );
+        rng.set_block_counter(1);
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+        // Fill the key stream in stages, to test the edge cases of the fill_u8 function:
+        let mut key_stream = [0u8; 114];
+        rng.fill_u8(&mut key_stream[0..5]);
+        rng.fill_u8(&mut key_stream[5..70]);
+        rng.fill_u8(&mut key_stream[70..114]);
+        let ciphertext: [u8; 114] = core::array::from_fn(|i| plaintext[i] ^ key_stream[i]);
+
+        assert_eq!(
+            ciphertext,
+            [
+                0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80, 0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d,
+                0x69, 0x81, 0xe9, 0x7e, 0x7a, 0xec, 0x1d, 0x43, 0x60, 0xc2, 0x0a, 0x27, 0xaf, 0xcc,
+                0xfd, 0x9f, 0xae, 0x0b, 0xf9, 0x1b, 0x65, 0xc5, 0x52, 0x47, 0x33, 0xab, 0x8f, 0x59,
+                0x3d, 0xab, 0xcd, 0x62, 0xb3, 0x57, 0x16, 0x39, 0xd6, 0x24, 0xe6, 0x51, 0x52, 0xab,
+                0x8f, 0x53, 0x0c, 0x35, 0x9f, 0x08, 0x61, 0xd8, 0x07, 0xca, 0x0d, 0xbf, 0x50, 0x0d,
+                0x6a, 0x61, 0x56, 0xa3, 0x8e, 0x08, 0x8a, 0x22, 0xb6, 0x5e, 0x52, 0xbc, 0x51, 0x4d,
+                0x16, 0xcc, 0xf8, 0x06, 0x81, 0x8c, 0xe9, 0x1a, 0xb7, 0x79, 0x37, 0x36, 0x5a, 0xf9,
+                0x0b, 0xbf, 0x74, 0xa3, 0x5b, 0xe6, 0xb4, 0x0b, 0x8e, 0xed, 0xf2, 0x78, 0x5e, 0x42,
+                0x87, 0x4d,
+            ]
+        );
+    }
+
+    #[test]
+    fn rfc8439_zero_key_stream() {
+        // These are test vectors 1 and 2 from appendix A.1 of RFC 8439
+        let mut rng = ChaCha20Ietf::from_seed(&[0; 32], [0; 12]);
+
+        let mut output = [0u8; 128];
+        rng.fill_u8(&mut output);
+
+        assert_eq!(
+            output,
+            [
+                0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90, 0x40, 0x5d, 0x6a, 0xe5, 0x53, 0x86,
+                0xbd, 0x28, 0xbd, 0xd2, 0x19, 0xb8, 0xa0, 0x8d, 0xed, 0x1a, 0xa8, 0x36, 0xef, 0xcc,
+                0x8b, 0x77, 0x0d, 0xc7, 0xda, 0x41, 0x59, 0x7c, 0x51, 0x57, 0x48, 0x8d, 0x77, 0x24,
+                0xe0, 0x3f, 0xb8, 0xd8, 0x4a, 0x37, 0x6a, 0x43, 0xb8, 0xf4, 0x15, 0x18, 0xa1, 0x1c,
+                0xc3, 0x87, 0xb6, 0x69, 0xb2, 0xee, 0x65, 0x86, 0x9f, 0x07, 0xe7, 0xbe, 0x55, 0x51,
+                0x38, 0x7a, 0x98, 0xba, 0x97, 0x7c, 0x73, 0x2d, 0x08, 0x0d, 0xcb, 0x0f, 0x29, 0xa0,
+                0x48, 0xe3, 0x65, 0x69, 0x12, 0xc6, 0x53, 0x3e, 0x32, 0xee, 0x7a, 0xed, 0x29, 0xb7,
+                0x21, 0x76, 0x9c, 0xe6, 0x4e, 0x43, 0xd5, 0x71, 0x33, 0xb0, 0x74, 0xd8, 0x39, 0xd5,
+                0x31, 0xed, 0x1f, 0x28, 0x51, 0x0a, 0xfb, 0x45, 0xac, 0xe1, 0x0a, 0x1f, 0x4b, 0x79,
+                0x4d, 0x6f,
+            ]
+        );
+    }
+
+    #[test]
+    fn counter_overflow_is_an_error() {
+        let mut rng = ChaCha20Ietf::from_seed(&[0; 32], [0; 12]);
+        rng.set_block_counter(u32::MAX);
+        assert_eq!(rng.try_fill_u8(&mut [0; 65]), Err(CounterOverflowError));
+        assert_eq!(rng.try_fill_u8(&mut [0; 60]), Ok(()));
+        assert_eq!(rng.try_fill_u8(&mut [0; 5]), Err(CounterOverflowError));
+        assert_eq!(rng.try_fill_u8(&mut [0; 4]), Ok(()));
+        assert_eq!(rng.try_fill_u8(&mut [0; 1]), Err(CounterOverflowError));
+        assert_eq!(rng.try_fill_u8(&mut []), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Max number of blocks exceeded")]
+    fn counter_overflow_panics_in_rng() {
+        let mut rng = ChaCha20Ietf::from_seed(&[0; 32], [0; 12]);
+        rng.set_block_counter(u32::MAX);
+        rng.fill_u8(&mut [0; 64]);
+        let _ = rng.random_u32();
+    }
+}
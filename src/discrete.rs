@@ -0,0 +1,872 @@
+// The single letter names are the ones used in the papers that the algorithms are from
+#![allow(clippy::cast_precision_loss, clippy::many_single_char_names)]
+
+use crate::distributions::{Distribution, ParameterError};
+use crate::rng::{random_nonzero_unit_f64, random_unit_f64, Rng};
+
+/// The values of `stirling_correction` for 0 to 9, where the series is not accurate enough.
+#[allow(clippy::unreadable_literal)]
+const STIRLING_CORRECTIONS: [f64; 10] = [
+    0.08106146679532726,
+    0.0413406959554093,
+    0.02767792568499834,
+    0.020790672103765093,
+    0.016644691189821193,
+    0.013876128823070748,
+    0.01189670994589177,
+    0.010411265261972096,
+    0.009255462182712733,
+    0.00833056343336287,
+];
+
+/// Returns the error of Stirling's approximation of ln(k!), which is
+/// ln(k!) - ((k + 0.5) * ln(k + 1) - (k + 1) + 0.5 * ln(2 * pi)).
+/// `k` must be a non-negative integer.
+fn stirling_correction(k: f64) -> f64 {
+    if k < 10.0 {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        return STIRLING_CORRECTIONS[k as usize];
+    }
+    let k_plus_1_squared = (k + 1.0) * (k + 1.0);
+    (1.0 / 12.0 - (1.0 / 360.0 - 1.0 / 1260.0 / k_plus_1_squared) / k_plus_1_squared) / (k + 1.0)
+}
+
+/// Returns ln((y + difference)! / y!), where `y` and `y + difference` are non-negative integers.
+///
+/// This avoids calculating the two log factorials separately, as they can be
+/// far too large for their difference to be accurate when they are large and close.
+/// The difference is passed separately as it may not be exact if calculated from two large floats.
+fn ln_factorial_ratio(y: f64, difference: f64) -> f64 {
+    let x = y + difference;
+    (x + 0.5) * (difference / (y + 1.0)).ln_1p() + difference * (y + 1.0).ln() - difference
+        + stirling_correction(x)
+        - stirling_correction(y)
+}
+
+/// The Poisson distribution, with a given mean (often called lambda).
+/// This is the distribution of the number of events that happen in a fixed interval
+/// when they happen independently at a constant average rate.
+///
+/// Small means are sampled by inversion, and larger means using the PTRS algorithm
+/// (transformed rejection with squeeze), so sampling is fast for all means.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Poisson, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let arrivals_per_hour = Poisson::new(4.5).unwrap();
+/// let arrivals = rng.sample(&arrivals_per_hour);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Poisson(PoissonMethod);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum PoissonMethod {
+    Inversion {
+        lambda: f64,
+        exp_minus_lambda: f64,
+    },
+    Ptrs {
+        lambda: f64,
+        a: f64,
+        b: f64,
+        ln_inverse_alpha: f64,
+        v_r: f64,
+    },
+}
+
+impl Poisson {
+    // The smallest mean to use PTRS for, which is the limit given in the paper
+    const PTRS_LIMIT: f64 = 10.0;
+
+    // The largest supported mean, which keeps the values well within the range of u64
+    const MAX_LAMBDA: f64 = (1_u64 << 63) as f64;
+
+    /// Creates a new Poisson distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `lambda`: The mean of the distribution
+    ///
+    /// returns: [Poisson], or an error if the mean is invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidRate] if `lambda` is not positive
+    /// or is larger than 2^63.
+    pub fn new(lambda: f64) -> Result<Self, ParameterError> {
+        if !(lambda > 0.0 && lambda <= Self::MAX_LAMBDA) {
+            return Err(ParameterError::InvalidRate);
+        }
+        if lambda < Self::PTRS_LIMIT {
+            return Ok(Self(PoissonMethod::Inversion {
+                lambda,
+                exp_minus_lambda: (-lambda).exp(),
+            }));
+        }
+        // The constants of Hörmann, "The transformed rejection method for generating
+        // Poisson random variables" (https://doi.org/10.1016/0167-6687(93)90997-4)
+        let b = 0.931 + 2.53 * lambda.sqrt();
+        Ok(Self(PoissonMethod::Ptrs {
+            lambda,
+            a: -0.059 + 0.02483 * b,
+            b,
+            ln_inverse_alpha: (1.1239 + 1.1328 / (b - 3.4)).ln(),
+            v_r: 0.9277 - 3.6224 / (b - 2.0),
+        }))
+    }
+
+    fn sample_inversion(lambda: f64, exp_minus_lambda: f64, rng: &mut impl Rng) -> u64 {
+        'restart: loop {
+            let mut u = random_unit_f64(rng);
+            let mut k = 0;
+            let mut probability = exp_minus_lambda;
+            while u > probability {
+                u -= probability;
+                k += 1;
+                probability *= lambda / k as f64;
+                if probability == 0.0 {
+                    // Rounding errors have made u larger than the sum of all probabilities
+                    continue 'restart;
+                }
+            }
+            return k;
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn sample_ptrs(
+        rng: &mut impl Rng,
+        lambda: f64,
+        a: f64,
+        b: f64,
+        ln_inverse_alpha: f64,
+        v_r: f64,
+    ) -> u64 {
+        loop {
+            let u = random_unit_f64(rng) - 0.5;
+            let v = random_unit_f64(rng);
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+            if k < 0.0 || !k.is_finite() {
+                continue;
+            }
+            if us >= 0.07 && v <= v_r {
+                return k as u64;
+            }
+            if us < 0.013 && v > us {
+                continue;
+            }
+            // ln(lambda^k * exp(-lambda) / k!), written in terms of Stirling's approximation
+            // of k! to keep it accurate for large lambdas
+            let difference = lambda - k - 1.0;
+            let ln_probability = k * (difference / (k + 1.0)).ln_1p()
+                - 0.5 * (k + 1.0).ln()
+                - difference
+                - 0.5 * (2.0 * core::f64::consts::PI).ln()
+                - stirling_correction(k);
+            if v.ln() + ln_inverse_alpha - (a / (us * us) + b).ln() <= ln_probability {
+                return k as u64;
+            }
+        }
+    }
+}
+
+impl Distribution<u64> for Poisson {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> u64 {
+        match self.0 {
+            PoissonMethod::Inversion {
+                lambda,
+                exp_minus_lambda,
+            } => Self::sample_inversion(lambda, exp_minus_lambda, rng),
+            PoissonMethod::Ptrs {
+                lambda,
+                a,
+                b,
+                ln_inverse_alpha,
+                v_r,
+            } => Self::sample_ptrs(rng, lambda, a, b, ln_inverse_alpha, v_r),
+        }
+    }
+}
+
+/// The binomial distribution, which is the distribution of the number of successes
+/// in `n` independent trials that each succeed with probability `p`.
+///
+/// Distributions with a small mean are sampled by inversion, and the others using the
+/// BTRS algorithm (transformed rejection with squeeze), so sampling is fast for all parameters.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Binomial, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let conversions = Binomial::new(1000, 0.03).unwrap();
+/// let converted_visitors = rng.sample(&conversions);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Binomial {
+    n: u64,
+    // The distribution is sampled with a p of at most 0.5, and the result
+    // is subtracted from n if it has been flipped.
+    flipped: bool,
+    method: BinomialMethod,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum BinomialMethod {
+    Constant,
+    Inversion {
+        q_to_the_n: f64,
+        p_over_q: f64,
+        a: f64,
+    },
+    Btrs {
+        a: f64,
+        b: f64,
+        c: f64,
+        alpha: f64,
+        v_r: f64,
+        ln_p_over_q: f64,
+        mode: f64,
+    },
+}
+
+impl Binomial {
+    // The smallest n * p to use BTRS for, which is the limit given in the paper
+    const BTRS_LIMIT: f64 = 10.0;
+
+    /// Creates a new binomial distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `n`: The number of trials
+    /// * `p`: The probability of success for each trial. Must be in the range [0, 1].
+    ///
+    /// returns: [Binomial], or an error if `p` is not a valid probability
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidProbability] if `p` is NaN or outside [0, 1].
+    pub fn new(n: u64, p: f64) -> Result<Self, ParameterError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(ParameterError::InvalidProbability);
+        }
+        let flipped = p > 0.5;
+        let p = if flipped { 1.0 - p } else { p };
+        let q = 1.0 - p;
+        let n_float = n as f64;
+        let method = if p == 0.0 || n == 0 {
+            BinomialMethod::Constant
+        } else if n_float * p < Self::BTRS_LIMIT {
+            BinomialMethod::Inversion {
+                q_to_the_n: (n_float * (-p).ln_1p()).exp(),
+                p_over_q: p / q,
+                a: (n_float + 1.0) * p / q,
+            }
+        } else {
+            // The constants of Hörmann, "The generation of binomial random variates"
+            // (https://doi.org/10.1080/00949659308811496)
+            let std_dev = (n_float * p * q).sqrt();
+            let b = 1.15 + 2.53 * std_dev;
+            BinomialMethod::Btrs {
+                a: -0.0873 + 0.0248 * b + 0.01 * p,
+                b,
+                c: n_float * p + 0.5,
+                alpha: (2.83 + 5.1 / b) * std_dev,
+                v_r: 0.92 - 4.2 / b,
+                ln_p_over_q: (p / q).ln(),
+                mode: ((n_float + 1.0) * p).floor(),
+            }
+        };
+        Ok(Self { n, flipped, method })
+    }
+
+    fn sample_inversion(&self, rng: &mut impl Rng, q_to_the_n: f64, p_over_q: f64, a: f64) -> u64 {
+        'restart: loop {
+            let mut u = random_unit_f64(rng);
+            let mut k = 0;
+            let mut probability = q_to_the_n;
+            while u > probability {
+                u -= probability;
+                k += 1;
+                if k > self.n {
+                    // Rounding errors have made u larger than the sum of all probabilities
+                    continue 'restart;
+                }
+                probability *= a / k as f64 - p_over_q;
+                if probability == 0.0 {
+                    // Rounding errors have made u larger than the sum of all probabilities
+                    continue 'restart;
+                }
+            }
+            return k;
+        }
+    }
+
+    #[allow(
+        clippy::too_many_arguments,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn sample_btrs(
+        &self,
+        rng: &mut impl Rng,
+        a: f64,
+        b: f64,
+        c: f64,
+        alpha: f64,
+        v_r: f64,
+        ln_p_over_q: f64,
+        mode: f64,
+    ) -> u64 {
+        let n = self.n as f64;
+        loop {
+            let u = random_unit_f64(rng) - 0.5;
+            let v = random_unit_f64(rng);
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + c).floor();
+            if !(0.0..=n).contains(&k) {
+                continue;
+            }
+            if us >= 0.07 && v <= v_r {
+                return (k as u64).min(self.n);
+            }
+            // ln(f(k) / f(mode)), where f is the probability mass function
+            let ln_relative_probability = ln_factorial_ratio(k, mode - k)
+                + ln_factorial_ratio(n - k, k - mode)
+                + (k - mode) * ln_p_over_q;
+            if (v * alpha / (a / (us * us) + b)).ln() <= ln_relative_probability {
+                return (k as u64).min(self.n);
+            }
+        }
+    }
+}
+
+impl Distribution<u64> for Binomial {
+    fn sample(&self, rng: &mut impl Rng) -> u64 {
+        let successes = match self.method {
+            BinomialMethod::Constant => 0,
+            BinomialMethod::Inversion {
+                q_to_the_n,
+                p_over_q,
+                a,
+            } => self.sample_inversion(rng, q_to_the_n, p_over_q, a),
+            BinomialMethod::Btrs {
+                a,
+                b,
+                c,
+                alpha,
+                v_r,
+                ln_p_over_q,
+                mode,
+            } => self.sample_btrs(rng, a, b, c, alpha, v_r, ln_p_over_q, mode),
+        };
+        if self.flipped {
+            self.n - successes
+        } else {
+            successes
+        }
+    }
+}
+
+/// The geometric distribution, which is the distribution of the number of failures
+/// before the first success in independent trials that each succeed with probability `p`.
+///
+/// Values are sampled by inversion, which takes constant time for all `p`.
+/// Values that are too large for a u64 (which can only happen for tiny `p`) are clamped to u64::MAX.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Geometric, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let failed_attempts = Geometric::new(0.25).unwrap();
+/// let failures = rng.sample(&failed_attempts);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Geometric {
+    // ln(1 - p), which is 0 if p is 1
+    ln_q: f64,
+}
+
+impl Geometric {
+    /// Creates a new geometric distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `p`: The probability of success for each trial. Must be in the range (0, 1].
+    ///
+    /// returns: [Geometric], or an error if `p` is not a valid probability
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidProbability] if `p` is NaN or outside (0, 1].
+    pub fn new(p: f64) -> Result<Self, ParameterError> {
+        if !(p > 0.0 && p <= 1.0) {
+            return Err(ParameterError::InvalidProbability);
+        }
+        Ok(Self { ln_q: (-p).ln_1p() })
+    }
+}
+
+impl Distribution<u64> for Geometric {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> u64 {
+        if self.ln_q.is_infinite() {
+            return 0;
+        }
+        // The probability of at least k failures is (1 - p)^k
+        (random_nonzero_unit_f64(rng).ln() / self.ln_q).floor() as u64
+    }
+}
+
+/// The hypergeometric distribution, which is the distribution of the number of successes
+/// when drawing a number of items without replacement from a population
+/// that contains a given number of successes.
+///
+/// Small samples are drawn item by item with exact integer ranges, and larger samples
+/// using the HRUA algorithm (ratio of uniforms), so sampling is fast for all parameters.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Hypergeometric, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// // Drawing 5 cards from a deck with 4 aces
+/// let aces_drawn = Hypergeometric::new(52, 4, 5).unwrap();
+/// let aces = rng.sample(&aces_drawn);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hypergeometric {
+    total: u64,
+    successes: u64,
+    draws: u64,
+    // The distribution is sampled with at most half the population as successes and
+    // at most half the population drawn. These are the parameters after that reduction.
+    reduced_successes: u64,
+    reduced_draws: u64,
+    method: HypergeometricMethod,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum HypergeometricMethod {
+    Draws,
+    Hrua {
+        a: f64,
+        h: f64,
+        mode: f64,
+        upper_bound: f64,
+    },
+}
+
+impl Hypergeometric {
+    // The largest number of draws that are sampled item by item
+    const MAX_ITEM_BY_ITEM_DRAWS: u64 = 16;
+
+    /// Creates a new hypergeometric distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `total`: The size of the population
+    /// * `successes`: The number of successes in the population
+    /// * `draws`: The number of items that are drawn from the population
+    ///
+    /// returns: [Hypergeometric], or an error if the parameters are inconsistent
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidCount] if `successes` or `draws` is larger than `total`.
+    pub fn new(total: u64, successes: u64, draws: u64) -> Result<Self, ParameterError> {
+        if successes > total || draws > total {
+            return Err(ParameterError::InvalidCount);
+        }
+        let reduced_successes = successes.min(total - successes);
+        let reduced_draws = draws.min(total - draws);
+        let method = if reduced_draws <= Self::MAX_ITEM_BY_ITEM_DRAWS || reduced_successes == 0 {
+            HypergeometricMethod::Draws
+        } else {
+            // The constants of Stadlober, "The ratio of uniforms approach for generating
+            // discrete random variates" (https://doi.org/10.1016/0377-0427(90)90349-5)
+            let total_float = total as f64;
+            let draws_float = reduced_draws as f64;
+            let p = reduced_successes as f64 / total_float;
+            let mean = draws_float * p;
+            let variance =
+                (total_float - draws_float) * draws_float * p * (1.0 - p) / (total_float - 1.0);
+            let c = (variance + 0.5).sqrt();
+            let a = mean + 0.5;
+            HypergeometricMethod::Hrua {
+                a,
+                // 2 * sqrt(2 / e) and 3 - 2 * sqrt(3 / e)
+                h: 1.715_527_769_921_413_5 * c + 0.898_916_162_058_898_8,
+                mode: ((draws_float + 1.0) * (reduced_successes as f64 + 1.0)
+                    / (total_float + 2.0))
+                    .floor(),
+                upper_bound: ((reduced_draws.min(reduced_successes) + 1) as f64)
+                    .min((a + 16.0 * c).floor()),
+            }
+        };
+        Ok(Self {
+            total,
+            successes,
+            draws,
+            reduced_successes,
+            reduced_draws,
+            method,
+        })
+    }
+
+    fn sample_draws(&self, rng: &mut impl Rng) -> u64 {
+        let mut remaining_successes = self.reduced_successes;
+        for drawn in 0..self.reduced_draws {
+            if remaining_successes == 0 {
+                break;
+            }
+            if rng.range(0..self.total - drawn) < remaining_successes {
+                remaining_successes -= 1;
+            }
+        }
+        self.reduced_successes - remaining_successes
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn sample_hrua(&self, rng: &mut impl Rng, a: f64, h: f64, mode: f64, upper_bound: f64) -> u64 {
+        let successes = self.reduced_successes as f64;
+        let draws = self.reduced_draws as f64;
+        let failures = (self.total - self.reduced_successes) as f64;
+        loop {
+            let u = random_nonzero_unit_f64(rng);
+            let v = random_unit_f64(rng);
+            let x = a + h * (v - 0.5) / u;
+            if !(0.0..upper_bound).contains(&x) {
+                continue;
+            }
+            let k = x.floor();
+            // ln(f(k) / f(mode)), where f is the probability mass function
+            let difference = mode - k;
+            let t = ln_factorial_ratio(k, difference)
+                + ln_factorial_ratio(successes - k, -difference)
+                + ln_factorial_ratio(draws - k, -difference)
+                + ln_factorial_ratio(failures - draws + k, difference);
+            if u * (4.0 - u) - 3.0 <= t {
+                return k as u64;
+            }
+            if u * (u - t) >= 1.0 {
+                continue;
+            }
+            if 2.0 * u.ln() <= t {
+                return k as u64;
+            }
+        }
+    }
+}
+
+impl Distribution<u64> for Hypergeometric {
+    fn sample(&self, rng: &mut impl Rng) -> u64 {
+        let mut successes = match self.method {
+            HypergeometricMethod::Draws => self.sample_draws(rng),
+            HypergeometricMethod::Hrua {
+                a,
+                h,
+                mode,
+                upper_bound,
+            } => self.sample_hrua(rng, a, h, mode, upper_bound),
+        };
+        // Undo the reductions: Drawing failures instead of successes,
+        // and counting the items that are not drawn instead of the ones that are
+        if self.reduced_successes != self.successes {
+            successes = self.reduced_draws - successes;
+        }
+        if self.reduced_draws != self.draws {
+            successes = self.successes - successes;
+        }
+        successes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    const SAMPLES: usize = 100_000;
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    /// Samples the distribution and asserts that the values fit the probability mass function.
+    /// Values beyond the end of `pmf` are counted together.
+    fn assert_fits(distribution: impl Distribution<u64>, pmf: &[f64]) {
+        // Merge adjacent values into bins with enough expected values for the test to be valid
+        let mut bin_of_value = Vec::new();
+        let mut expected = vec![0.0];
+        for probability in pmf {
+            if *expected.last().unwrap() >= 20.0 {
+                expected.push(0.0);
+            }
+            *expected.last_mut().unwrap() += probability * SAMPLES as f64;
+            bin_of_value.push(expected.len() - 1);
+        }
+        let remainder = (1.0 - pmf.iter().sum::<f64>()).max(0.0) * SAMPLES as f64;
+        *expected.last_mut().unwrap() += remainder;
+
+        let mut rng = rng();
+        let mut observed = vec![0; expected.len()];
+        for value in distribution.sample_iter(&mut rng).take(SAMPLES) {
+            let value = usize::try_from(value).unwrap();
+            observed[*bin_of_value.get(value).unwrap_or(&(expected.len() - 1))] += 1;
+        }
+        let chi_square = chi_square(&observed, &expected);
+
+        // The Wilson-Hilferty approximation of the critical value at p = 0.001
+        let degrees_of_freedom = (expected.len() - 1) as f64;
+        let z = 2.0 / (9.0 * degrees_of_freedom);
+        let critical_value = degrees_of_freedom * (1.0 - z + 3.09 * z.sqrt()).powi(3);
+        assert!(
+            chi_square < critical_value,
+            "{chi_square} >= {critical_value}"
+        );
+    }
+
+    fn poisson_pmf(lambda: f64, len: usize) -> Vec<f64> {
+        let mut probability = (-lambda).exp();
+        (0..len)
+            .map(|k| {
+                let result = probability;
+                probability *= lambda / (k + 1) as f64;
+                result
+            })
+            .collect()
+    }
+
+    fn binomial_pmf(n: u64, p: f64) -> Vec<f64> {
+        let mut probability = (1.0 - p).powf(n as f64);
+        (0..=n)
+            .map(|k| {
+                let result = probability;
+                probability *= (n - k) as f64 / (k + 1) as f64 * p / (1.0 - p);
+                result
+            })
+            .collect()
+    }
+
+    fn hypergeometric_pmf(total: u64, successes: u64, draws: u64) -> Vec<f64> {
+        let ln_choose = |n: u64, k: u64| {
+            (0..k)
+                .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+                .sum::<f64>()
+        };
+        let failures = total - successes;
+        (0..=draws.min(successes))
+            .map(|k| {
+                if draws - k > failures {
+                    0.0
+                } else {
+                    (ln_choose(successes, k) + ln_choose(failures, draws - k)
+                        - ln_choose(total, draws))
+                    .exp()
+                }
+            })
+            .collect()
+    }
+
+    fn mean(distribution: impl Distribution<u64>, count: usize) -> f64 {
+        let mut rng = rng();
+        distribution
+            .sample_iter(&mut rng)
+            .take(count)
+            .map(|value| value as f64)
+            .sum::<f64>()
+            / count as f64
+    }
+
+    #[test]
+    fn ln_factorial_ratio_is_accurate() {
+        let ln_factorial = |n: u64| (1..=n).map(|i| (i as f64).ln()).sum::<f64>();
+        for (x, y) in [(0, 0), (5, 0), (0, 7), (12, 3), (100, 150), (1000, 999)] {
+            let expected = ln_factorial(x) - ln_factorial(y);
+            let actual = ln_factorial_ratio(y as f64, x as f64 - y as f64);
+            assert!((actual - expected).abs() < 1e-9, "{x} {y}: {actual}");
+        }
+        // ln((10^15 + 1)! / 10^15!) = ln(10^15 + 1)
+        assert!((ln_factorial_ratio(1e15, 1.0) - (1e15_f64 + 1.0).ln()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn poisson_with_small_lambda_fits_distribution() {
+        assert_fits(Poisson::new(3.5).unwrap(), &poisson_pmf(3.5, 15));
+    }
+
+    #[test]
+    fn poisson_with_large_lambda_fits_distribution() {
+        assert_fits(Poisson::new(47.3).unwrap(), &poisson_pmf(47.3, 90));
+    }
+
+    #[test]
+    fn poisson_with_huge_lambda() {
+        let lambda = 1e12;
+        let mean = mean(Poisson::new(lambda).unwrap(), 10_000);
+        // The standard deviation of the mean is 1e4
+        assert!((mean - lambda).abs() < 5e4, "{mean}");
+    }
+
+    #[test]
+    fn binomial_with_small_mean_fits_distribution() {
+        assert_fits(Binomial::new(40, 0.15).unwrap(), &binomial_pmf(40, 0.15));
+    }
+
+    #[test]
+    fn binomial_with_large_mean_fits_distribution() {
+        assert_fits(Binomial::new(500, 0.3).unwrap(), &binomial_pmf(500, 0.3));
+    }
+
+    #[test]
+    fn binomial_with_large_p_fits_distribution() {
+        assert_fits(Binomial::new(30, 0.9).unwrap(), &binomial_pmf(30, 0.9));
+        assert_fits(Binomial::new(200, 0.75).unwrap(), &binomial_pmf(200, 0.75));
+    }
+
+    #[test]
+    fn binomial_with_huge_n() {
+        let n = 1_000_000_000_000_000;
+        let distribution = Binomial::new(n, 0.25).unwrap();
+        let mut rng = rng();
+        assert!(distribution
+            .sample_iter(&mut rng)
+            .take(1000)
+            .all(|k| k <= n));
+        let mean = mean(distribution, 10_000);
+        // The standard deviation of the mean is about 1.4e5
+        assert!((mean - 2.5e14).abs() < 7e5, "{mean}");
+    }
+
+    #[test]
+    fn binomial_with_max_n_and_tiny_p() {
+        /// Returns the largest possible value first, so that the first sample
+        /// is larger than the sum of the probabilities as they are calculated.
+        struct LargeFirst(Option<Xoshiro256pp>);
+
+        impl Rng for LargeFirst {
+            fn random_u32(&mut self) -> u32 {
+                self.random_u64() as u32
+            }
+
+            fn random_u64(&mut self) -> u64 {
+                match &mut self.0 {
+                    Some(rng) => rng.random_u64(),
+                    None => {
+                        self.0 = Some(rng());
+                        u64::MAX
+                    }
+                }
+            }
+        }
+
+        for p in [1e-19, 1e-20, 3e-20] {
+            let distribution = Binomial::new(u64::MAX, p).unwrap();
+            let mut rng = LargeFirst(None);
+            assert!(rng.sample(&distribution) < 100);
+        }
+    }
+
+    #[test]
+    fn binomial_extremes() {
+        let mut rng = rng();
+        for _ in 0..100 {
+            assert_eq!(rng.sample(&Binomial::new(10, 0.0).unwrap()), 0);
+            assert_eq!(rng.sample(&Binomial::new(10, 1.0).unwrap()), 10);
+            assert_eq!(rng.sample(&Binomial::new(0, 0.5).unwrap()), 0);
+        }
+    }
+
+    #[test]
+    fn geometric_fits_distribution() {
+        let p = 0.2_f64;
+        let pmf: Vec<f64> = (0..40).map(|k| p * (1.0 - p).powi(k)).collect();
+        assert_fits(Geometric::new(p).unwrap(), &pmf);
+    }
+
+    #[test]
+    fn geometric_with_p_of_1_is_always_0() {
+        let mut rng = rng();
+        let distribution = Geometric::new(1.0).unwrap();
+        assert!(distribution.sample_iter(&mut rng).take(100).all(|k| k == 0));
+    }
+
+    #[test]
+    fn hypergeometric_with_few_draws_fits_distribution() {
+        assert_fits(
+            Hypergeometric::new(50, 20, 8).unwrap(),
+            &hypergeometric_pmf(50, 20, 8),
+        );
+    }
+
+    #[test]
+    fn hypergeometric_with_many_draws_fits_distribution() {
+        assert_fits(
+            Hypergeometric::new(10_000, 3_000, 500).unwrap(),
+            &hypergeometric_pmf(10_000, 3_000, 500),
+        );
+    }
+
+    #[test]
+    fn hypergeometric_reductions_fit_distribution() {
+        // More than half of the population are successes and/or drawn
+        for (total, successes, draws) in [(60, 45, 10), (60, 12, 55), (1_000, 700, 900)] {
+            assert_fits(
+                Hypergeometric::new(total, successes, draws).unwrap(),
+                &hypergeometric_pmf(total, successes, draws),
+            );
+        }
+    }
+
+    #[test]
+    fn hypergeometric_with_huge_population() {
+        let distribution =
+            Hypergeometric::new(1 << 62, 1 << 60, 2_000_000_000_000_000_000).unwrap();
+        let mean = mean(distribution, 10_000);
+        // The standard deviation of the mean is about 4.6e6
+        assert!((mean - 5e17).abs() < 2.5e7, "{mean}");
+    }
+
+    #[test]
+    fn hypergeometric_extremes() {
+        let mut rng = rng();
+        for _ in 0..100 {
+            assert_eq!(rng.sample(&Hypergeometric::new(10, 0, 5).unwrap()), 0);
+            assert_eq!(rng.sample(&Hypergeometric::new(10, 10, 5).unwrap()), 5);
+            assert_eq!(rng.sample(&Hypergeometric::new(10, 4, 10).unwrap()), 4);
+            assert_eq!(rng.sample(&Hypergeometric::new(10, 4, 0).unwrap()), 0);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(Poisson::new(0.0), Err(ParameterError::InvalidRate));
+        assert_eq!(Poisson::new(f64::NAN), Err(ParameterError::InvalidRate));
+        assert_eq!(Poisson::new(1e20), Err(ParameterError::InvalidRate));
+        assert_eq!(
+            Binomial::new(10, 1.5),
+            Err(ParameterError::InvalidProbability)
+        );
+        assert_eq!(Geometric::new(0.0), Err(ParameterError::InvalidProbability));
+        assert_eq!(
+            Geometric::new(f64::NAN),
+            Err(ParameterError::InvalidProbability)
+        );
+        assert_eq!(
+            Hypergeometric::new(10, 11, 5),
+            Err(ParameterError::InvalidCount)
+        );
+        assert_eq!(
+            Hypergeometric::new(10, 5, 11),
+            Err(ParameterError::InvalidCount)
+        );
+    }
+}
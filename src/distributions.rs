@@ -0,0 +1,408 @@
+#![allow(clippy::module_name_repetitions)]
+
+use crate::rng::{random_unit_f32, random_unit_f64, Rng, ValueFromRng};
+use core::fmt;
+use core::fmt::Debug;
+
+/// This is the trait implemented by all probability distributions
+/// that values can be sampled from using an [Rng].
+///
+/// Implementing this trait for your own type makes it usable with
+/// [Rng::sample](crate::Rng::sample()) for all the random generators in this crate.
+pub trait Distribution<T> {
+    /// Draws a single value from the distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to draw the value with
+    ///
+    /// returns: A value drawn from the distribution
+    fn sample(&self, rng: &mut impl Rng) -> T;
+
+    /// Provides an iterator that emits values drawn from the distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to draw the values with
+    ///
+    /// returns: An iterator that outputs values drawn from the distribution. Never None.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallrand::{Bernoulli, Distribution, SmallRng};
+    /// let mut rng = SmallRng::from_seed(42);
+    /// let coin_flips = Bernoulli::new(0.5).unwrap().sample_iter(&mut rng).take(10).collect::<Vec<bool>>();
+    /// ```
+    #[inline]
+    fn sample_iter<'a>(self, rng: &'a mut impl Rng) -> impl Iterator<Item = T> + 'a
+    where
+        Self: Sized + 'a,
+        T: 'a,
+    {
+        core::iter::from_fn(move || Some(self.sample(rng)))
+    }
+}
+
+impl<T, D> Distribution<T> for &D
+where
+    D: Distribution<T> + ?Sized,
+{
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> T {
+        (*self).sample(rng)
+    }
+}
+
+/// The distribution used by [Rng::random](crate::Rng::random()):
+/// Integers are uniformly distributed over all possible values of the type,
+/// bools are true and false with equal probability,
+/// and floats are uniformly distributed in the half-open interval [0, 1).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Standard;
+
+impl<T> Distribution<T> for Standard
+where
+    T: ValueFromRng,
+{
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> T {
+        T::value_from_rng(rng)
+    }
+}
+
+/// A uniform distribution of floats in the open interval (0, 1), which never generates 0 or 1.
+///
+/// This is useful for algorithms that cannot handle 0, like those that take the logarithm
+/// of the value. The full precision of the mantissa is used, just like for
+/// [Rng::random](crate::Rng::random()), and values are drawn again until they are not 0.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Open01;
+
+impl Distribution<f32> for Open01 {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> f32 {
+        loop {
+            let value = random_unit_f32(rng);
+            if value > 0.0 {
+                return value;
+            }
+        }
+    }
+}
+
+impl Distribution<f64> for Open01 {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        loop {
+            let value = random_unit_f64(rng);
+            if value > 0.0 {
+                return value;
+            }
+        }
+    }
+}
+
+/// A uniform distribution of floats in the half-open interval (0, 1], which never generates 0.
+///
+/// This is the mirror image of [Standard], and is useful for algorithms that cannot
+/// handle 0 but can handle 1, like the Box-Muller transform.
+/// Each value is the float above a value drawn from [0, 1),
+/// which corresponds to rounding the ideal real number up rather than down.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpenClosed01;
+
+impl Distribution<f32> for OpenClosed01 {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> f32 {
+        // The bit pattern of a positive float is one below that of the next float
+        f32::from_bits(random_unit_f32(rng).to_bits() + 1)
+    }
+}
+
+impl Distribution<f64> for OpenClosed01 {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        // The bit pattern of a positive float is one below that of the next float
+        f64::from_bits(random_unit_f64(rng).to_bits() + 1)
+    }
+}
+
+/// The Bernoulli distribution, which generates true with probability `p`
+/// and false with probability `1 - p`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Bernoulli {
+    // A u64 value is true if it is below this threshold,
+    // except for ALWAYS_TRUE which is always true.
+    threshold: u64,
+}
+
+impl Bernoulli {
+    // u64::MAX cannot be used as a normal threshold since a p of 1 would need 2^64,
+    // so it is used to represent "always true" instead.
+    const ALWAYS_TRUE: u64 = u64::MAX;
+
+    /// Creates a new Bernoulli distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `p`: The probability of generating true. Must be in the range [0, 1].
+    ///
+    /// returns: [Bernoulli], or an error if `p` is not a valid probability
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidProbability] if `p` is NaN or outside [0, 1].
+    pub fn new(p: f64) -> Result<Self, ParameterError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(ParameterError::InvalidProbability);
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let threshold = if p >= 1.0 {
+            Self::ALWAYS_TRUE
+        } else {
+            (p * 2_f64.powi(64)) as u64
+        };
+        Ok(Self { threshold })
+    }
+}
+
+impl Distribution<bool> for Bernoulli {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> bool {
+        self.threshold == Self::ALWAYS_TRUE || rng.random_u64() < self.threshold
+    }
+}
+
+/// This is the trait for the float types that the continuous distributions support,
+/// which are f32 and f64.
+/// The distributions do their calculations using f64 regardless of the type.
+pub trait Float: Copy + PartialOrd + Debug {
+    /// Converts an f64 to this type, rounding to nearest if it does not fit exactly.
+    fn from_f64(value: f64) -> Self;
+
+    /// Converts this value to an f64.
+    fn to_f64(self) -> f64;
+}
+
+impl Float for f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Float for f64 {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// The error returned when a distribution is constructed with invalid parameters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParameterError {
+    /// A probability was NaN or outside the range [0, 1].
+    InvalidProbability,
+    /// A mean was not finite.
+    InvalidMean,
+    /// A standard deviation was negative or not finite.
+    InvalidStdDev,
+    /// A rate was not positive and finite.
+    InvalidRate,
+    /// A shape parameter was not positive and finite.
+    InvalidShape,
+    /// A scale parameter was not positive and finite.
+    InvalidScale,
+    /// A number of degrees of freedom was not positive and finite.
+    InvalidDegreesOfFreedom,
+    /// A count was larger than the size of the population it is taken from.
+    InvalidCount,
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidProbability => "probability must be in the range [0, 1]",
+            Self::InvalidMean => "mean must be finite",
+            Self::InvalidStdDev => "standard deviation must be finite and non-negative",
+            Self::InvalidRate => "rate must be finite and positive",
+            Self::InvalidShape => "shape must be finite and positive",
+            Self::InvalidScale => "scale must be finite and positive",
+            Self::InvalidDegreesOfFreedom => "degrees of freedom must be finite and positive",
+            Self::InvalidCount => "count must not be larger than the population",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParameterError {}
+
+/// Calculates the chi-square statistic for a set of observed and expected counts.
+#[cfg(test)]
+pub(crate) fn chi_square(observed: &[usize], expected: &[f64]) -> f64 {
+    assert_eq!(observed.len(), expected.len());
+    observed
+        .iter()
+        .zip(expected)
+        .map(|(&observed, &expected)| {
+            #[allow(clippy::cast_precision_loss)]
+            let difference = observed as f64 - expected;
+            difference * difference / expected
+        })
+        .sum()
+}
+
+/// Counts how many values fall between each of the boundaries,
+/// and returns the chi-square statistic assuming that all bins are equally probable.
+#[cfg(test)]
+pub(crate) fn chi_square_equal_bins(values: impl Iterator<Item = f64>, boundaries: &[f64]) -> f64 {
+    let mut count = vec![0_usize; boundaries.len() + 1];
+    let mut total = 0_u32;
+    for value in values {
+        count[boundaries.partition_point(|boundary| *boundary < value)] += 1;
+        total += 1;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let expected = vec![f64::from(total) / count.len() as f64; count.len()];
+    chi_square(&count, &expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::ConstantRng;
+    use crate::{ChaCha12, SmallRng, SplitMix, StdRng, Xoshiro256pp};
+
+    fn sample_with<R: Rng>(mut rng: R) {
+        let coin = Bernoulli::new(0.5).unwrap();
+        let flips = coin
+            .sample_iter(&mut rng)
+            .take(1000)
+            .filter(|flip| *flip)
+            .count();
+        assert!(flips > 400 && flips < 600, "{flips}");
+    }
+
+    #[test]
+    fn distributions_work_with_all_generators() {
+        sample_with(SmallRng::from_seed(42));
+        sample_with(StdRng::from_entropy(&mut SplitMix::new(42)));
+        sample_with(ChaCha12::from_seed(&[42; 32], [0; 8]));
+        sample_with(Xoshiro256pp::from_entropy(&mut SplitMix::new(42)));
+    }
+
+    #[test]
+    fn standard_matches_random() {
+        let mut rng1 = SmallRng::from_seed(42);
+        let mut rng2 = SmallRng::from_seed(42);
+        for _ in 0..100 {
+            assert_eq!(rng1.sample::<u32, _>(&Standard), rng2.random::<u32>());
+            assert_eq!(rng1.sample::<bool, _>(&Standard), rng2.random::<bool>());
+            assert_eq!(rng1.sample::<f64, _>(&Standard), rng2.random::<f64>());
+        }
+    }
+
+    #[test]
+    fn open_closed_01_limits() {
+        let mut rng = ConstantRng(u64::MAX);
+        assert_eq!(rng.sample::<f64, _>(&OpenClosed01), 1.0);
+        assert_eq!(rng.sample::<f32, _>(&OpenClosed01), 1.0);
+        assert!(rng.sample::<f64, _>(&Open01) < 1.0);
+        assert!(rng.sample::<f32, _>(&Open01) < 1.0);
+        let mut rng = ConstantRng(0);
+        assert_eq!(rng.sample::<f64, _>(&OpenClosed01), f64::from_bits(1));
+        assert_eq!(rng.sample::<f32, _>(&OpenClosed01), f32::from_bits(1));
+    }
+
+    #[test]
+    fn open_01_is_uniform() {
+        let mut rng = SmallRng::from_seed(42);
+        let boundaries = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+        let open: Vec<f64> = Distribution::<f64>::sample_iter(Open01, &mut rng)
+            .take(10_000)
+            .collect();
+        assert!(open.iter().all(|value| *value > 0.0 && *value < 1.0));
+        let chi_square = chi_square_equal_bins(open.into_iter(), &boundaries);
+        // The critical value for 9 degrees of freedom at p = 0.001 is 27.88
+        assert!(chi_square < 27.88, "{chi_square}");
+        let open_closed: Vec<f64> = Distribution::<f64>::sample_iter(OpenClosed01, &mut rng)
+            .take(10_000)
+            .collect();
+        assert!(open_closed
+            .iter()
+            .all(|value| *value > 0.0 && *value <= 1.0));
+        let chi_square = chi_square_equal_bins(open_closed.into_iter(), &boundaries);
+        assert!(chi_square < 27.88, "{chi_square}");
+        for _ in 0..1000 {
+            let value: f32 = rng.sample(&Open01);
+            assert!(value > 0.0 && value < 1.0);
+            let value: f32 = rng.sample(&OpenClosed01);
+            assert!(value > 0.0 && value <= 1.0);
+        }
+    }
+
+    #[test]
+    fn sample_through_reference() {
+        let mut rng1 = SmallRng::from_seed(42);
+        let mut rng2 = SmallRng::from_seed(42);
+        let distribution = Bernoulli::new(0.25).unwrap();
+        assert_eq!(
+            (&distribution)
+                .sample_iter(&mut rng1)
+                .take(32)
+                .collect::<Vec<_>>(),
+            distribution
+                .sample_iter(&mut rng2)
+                .take(32)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn bernoulli_extremes() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let never = Bernoulli::new(0.0).unwrap();
+        let always = Bernoulli::new(1.0).unwrap();
+        for _ in 0..1000 {
+            assert!(!rng.sample(&never));
+            assert!(rng.sample(&always));
+        }
+    }
+
+    #[test]
+    fn bernoulli_probability() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let distribution = Bernoulli::new(0.1).unwrap();
+        let count = distribution
+            .sample_iter(&mut rng)
+            .take(100_000)
+            .filter(|v| *v)
+            .count();
+        assert!((9_500..10_500).contains(&count), "{count}");
+    }
+
+    #[test]
+    fn bernoulli_invalid_probability() {
+        assert_eq!(
+            Bernoulli::new(-0.1),
+            Err(ParameterError::InvalidProbability)
+        );
+        assert_eq!(Bernoulli::new(1.1), Err(ParameterError::InvalidProbability));
+        assert_eq!(
+            Bernoulli::new(f64::NAN),
+            Err(ParameterError::InvalidProbability)
+        );
+    }
+}
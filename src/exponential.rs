@@ -0,0 +1,133 @@
+use crate::distributions::{Distribution, Float, ParameterError};
+use crate::rng::{random_nonzero_unit_f64, random_unit_f64, Rng};
+use crate::ziggurat_tables::{EXP_F, EXP_R, EXP_X};
+use core::marker::PhantomData;
+
+/// Generates a value from the exponential distribution with rate 1.
+pub(crate) fn standard_exponential(rng: &mut impl Rng) -> f64 {
+    // This is the Ziggurat algorithm, see standard_normal in normal.rs for details.
+    // The exponential distribution is memoryless, so the tail beyond R is just
+    // another exponential distribution shifted by R, which we sample by inversion.
+    loop {
+        let layer = (rng.random_u32() & 0xff) as usize;
+        let x = random_unit_f64(rng) * EXP_X[layer];
+        if x < EXP_X[layer + 1] {
+            return x;
+        }
+        if layer == 0 {
+            return EXP_R - random_nonzero_unit_f64(rng).ln();
+        }
+        if EXP_F[layer] + (EXP_F[layer + 1] - EXP_F[layer]) * random_unit_f64(rng) < (-x).exp() {
+            return x;
+        }
+    }
+}
+
+/// The exponential distribution, with a given rate (often called lambda).
+/// This is the distribution of the time between events that happen independently
+/// at a constant average rate.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Exp, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let inter_arrival_time = Exp::new(2.0).unwrap();
+/// let value: f64 = rng.sample(&inter_arrival_time);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Exp<F> {
+    inverse_rate: f64,
+    phantom: PhantomData<F>,
+}
+
+impl<F: Float> Exp<F> {
+    /// Creates a new exponential distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate`: The rate parameter. The mean of the distribution is `1 / rate`.
+    ///
+    /// returns: [Exp], or an error if the rate is invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidRate] if `rate` is not positive and finite.
+    pub fn new(rate: F) -> Result<Self, ParameterError> {
+        let rate = rate.to_f64();
+        if !(rate.is_finite() && rate > 0.0) {
+            return Err(ParameterError::InvalidRate);
+        }
+        Ok(Self {
+            inverse_rate: 1.0 / rate,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<F: Float> Distribution<F> for Exp<F> {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> F {
+        F::from_f64(standard_exponential(rng) * self.inverse_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square_equal_bins;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    #[test]
+    fn exponential_fits_distribution() {
+        // The quantiles of the exponential distribution are -ln(1 - p) / rate
+        let rate = 2.5;
+        let quantiles: Vec<f64> = (1..20)
+            .map(|i| -(1.0 - f64::from(i) / 20.0).ln() / rate)
+            .collect();
+        let distribution = Exp::new(rate).unwrap();
+        let mut rng = rng();
+        let chi_square =
+            chi_square_equal_bins(distribution.sample_iter(&mut rng).take(200_000), &quantiles);
+        // The critical value for 19 degrees of freedom at p = 0.001 is 43.82
+        assert!(chi_square < 43.82, "{chi_square}");
+    }
+
+    #[test]
+    fn exponential_tail_fits_distribution() {
+        // The probability of a value beyond R is exp(-R) = 4.5e-4,
+        // and the tail beyond R is again exponential
+        let mut rng = rng();
+        let tail: Vec<f64> = (0..1_000_000)
+            .map(|_| standard_exponential(&mut rng))
+            .filter(|x| *x > EXP_R)
+            .collect();
+        assert!((380..530).contains(&tail.len()), "{}", tail.len());
+        let quantiles: Vec<f64> = (1..4)
+            .map(|i| EXP_R - (1.0 - f64::from(i) / 4.0).ln())
+            .collect();
+        let chi_square = chi_square_equal_bins(tail.into_iter(), &quantiles);
+        // The critical value for 3 degrees of freedom at p = 0.001 is 16.27
+        assert!(chi_square < 16.27, "{chi_square}");
+    }
+
+    #[test]
+    fn exponential_f32() {
+        let mut rng = rng();
+        let distribution = Exp::new(0.5_f32).unwrap();
+        let mean = distribution.sample_iter(&mut rng).take(10_000).sum::<f32>() / 10_000.0;
+        assert!((mean - 2.0).abs() < 0.05, "{mean}");
+    }
+
+    #[test]
+    fn invalid_rate() {
+        assert_eq!(Exp::new(0.0), Err(ParameterError::InvalidRate));
+        assert_eq!(Exp::new(-1.0), Err(ParameterError::InvalidRate));
+        assert_eq!(Exp::new(f64::NAN), Err(ParameterError::InvalidRate));
+        assert_eq!(Exp::new(f32::INFINITY), Err(ParameterError::InvalidRate));
+    }
+}
@@ -0,0 +1,427 @@
+use crate::distributions::{Distribution, Float, ParameterError};
+use crate::exponential::standard_exponential;
+use crate::normal::standard_normal;
+use crate::rng::{random_nonzero_unit_f64, random_unit_f64, Rng};
+use core::marker::PhantomData;
+
+/// Samples the gamma distribution with a scale of 1.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct StandardGamma {
+    shape: f64,
+    // The constants of the Marsaglia-Tsang algorithm, for a shape of at least 1
+    d: f64,
+    c: f64,
+}
+
+impl StandardGamma {
+    fn new(shape: f64) -> Self {
+        // Shapes below 1 are sampled using a shape one larger, see below
+        let d = if shape < 1.0 { shape + 1.0 } else { shape } - 1.0 / 3.0;
+        Self {
+            shape,
+            d,
+            c: 1.0 / (9.0 * d).sqrt(),
+        }
+    }
+
+    // A shape of exactly 1 is the exponential distribution, which is faster to sample
+    #[allow(clippy::float_cmp)]
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        if self.shape == 1.0 {
+            return standard_exponential(rng);
+        }
+        let value = self.sample_marsaglia_tsang(rng);
+        if self.shape < 1.0 {
+            // If X ~ Gamma(shape + 1) and U ~ Uniform(0, 1), then
+            // X * U^(1 / shape) ~ Gamma(shape).
+            // This is done in the log domain to avoid underflow for small shapes.
+            (value.ln() + random_nonzero_unit_f64(rng).ln() / self.shape).exp()
+        } else {
+            value
+        }
+    }
+
+    fn sample_marsaglia_tsang(&self, rng: &mut impl Rng) -> f64 {
+        // Marsaglia and Tsang, "A Simple Method for Generating Gamma Variables"
+        // (https://doi.org/10.1145/358407.358414)
+        loop {
+            let x = standard_normal(rng);
+            let v = 1.0 + self.c * x;
+            if v <= 0.0 {
+                continue;
+            }
+            let v = v * v * v;
+            let u = random_nonzero_unit_f64(rng);
+            let x_squared = x * x;
+            // The squeeze avoids calculating logarithms in most cases
+            if u < 1.0 - 0.0331 * x_squared * x_squared
+                || u.ln() < 0.5 * x_squared + self.d * (1.0 - v + v.ln())
+            {
+                return self.d * v;
+            }
+        }
+    }
+}
+
+fn is_positive_and_finite(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// The gamma distribution, with a given shape (often called k) and scale (often called theta).
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Gamma, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let gamma = Gamma::new(2.0, 0.5).unwrap();
+/// let value: f64 = rng.sample(&gamma);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Gamma<F> {
+    standard: StandardGamma,
+    scale: f64,
+    phantom: PhantomData<F>,
+}
+
+impl<F: Float> Gamma<F> {
+    /// Creates a new gamma distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape`: The shape parameter
+    /// * `scale`: The scale parameter. This is the inverse of the rate parameter.
+    ///
+    /// returns: [Gamma], or an error if the parameters are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidShape] if `shape` is not positive and finite, and
+    /// [ParameterError::InvalidScale] if `scale` is not positive and finite.
+    pub fn new(shape: F, scale: F) -> Result<Self, ParameterError> {
+        let shape = shape.to_f64();
+        let scale = scale.to_f64();
+        if !is_positive_and_finite(shape) {
+            return Err(ParameterError::InvalidShape);
+        }
+        if !is_positive_and_finite(scale) {
+            return Err(ParameterError::InvalidScale);
+        }
+        Ok(Self {
+            standard: StandardGamma::new(shape),
+            scale,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<F: Float> Distribution<F> for Gamma<F> {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> F {
+        F::from_f64(self.standard.sample(rng) * self.scale)
+    }
+}
+
+/// The chi-squared distribution with a given number of degrees of freedom.
+/// This is the distribution of the sum of the squares of that many
+/// independent standard normal values.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{ChiSquared, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let chi_squared = ChiSquared::new(3.0).unwrap();
+/// let value: f64 = rng.sample(&chi_squared);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChiSquared<F> {
+    gamma: Gamma<F>,
+}
+
+impl<F: Float> ChiSquared<F> {
+    /// Creates a new chi-squared distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `degrees_of_freedom`: The number of degrees of freedom. Does not need to be an integer.
+    ///
+    /// returns: [ChiSquared], or an error if the degrees of freedom are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidDegreesOfFreedom] if `degrees_of_freedom`
+    /// is not positive and finite.
+    pub fn new(degrees_of_freedom: F) -> Result<Self, ParameterError> {
+        // The chi-squared distribution is a special case of the gamma distribution.
+        // The shape is checked here, as half of a tiny f32 rounds to zero.
+        let shape = F::from_f64(0.5 * degrees_of_freedom.to_f64());
+        if !is_positive_and_finite(shape.to_f64()) {
+            return Err(ParameterError::InvalidDegreesOfFreedom);
+        }
+        Ok(Self {
+            gamma: Gamma::new(shape, F::from_f64(2.0))?,
+        })
+    }
+}
+
+impl<F: Float> Distribution<F> for ChiSquared<F> {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> F {
+        self.gamma.sample(rng)
+    }
+}
+
+/// The beta distribution, with shape parameters alpha and beta.
+/// This is a distribution of values in the range [0, 1], which is often used
+/// as a prior for probabilities.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Beta, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let beta = Beta::new(2.0, 5.0).unwrap();
+/// let probability: f64 = rng.sample(&beta);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Beta<F> {
+    // The gamma distributions for X and Y, with shapes alpha and beta (see sample)
+    x_gamma: StandardGamma,
+    y_gamma: StandardGamma,
+    phantom: PhantomData<F>,
+}
+
+impl<F: Float> Beta<F> {
+    /// Creates a new beta distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha`: The first shape parameter
+    /// * `beta`: The second shape parameter
+    ///
+    /// returns: [Beta], or an error if the parameters are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidShape] if `alpha` or `beta` is not positive and finite.
+    pub fn new(alpha: F, beta: F) -> Result<Self, ParameterError> {
+        let alpha = alpha.to_f64();
+        let beta = beta.to_f64();
+        if !(is_positive_and_finite(alpha) && is_positive_and_finite(beta)) {
+            return Err(ParameterError::InvalidShape);
+        }
+        Ok(Self {
+            x_gamma: StandardGamma::new(alpha),
+            y_gamma: StandardGamma::new(beta),
+            phantom: PhantomData,
+        })
+    }
+
+    fn sample_johnk(&self, rng: &mut impl Rng) -> f64 {
+        // Jöhnk's algorithm, which is efficient when both shapes are below 1.
+        // If X = U^(1 / alpha) and Y = V^(1 / beta), then X / (X + Y) is beta distributed
+        // given that X + Y <= 1.
+        // This is done in the log domain, as X and Y can underflow for small shapes.
+        loop {
+            let log_x = random_nonzero_unit_f64(rng).ln() / self.x_gamma.shape;
+            let log_y = random_nonzero_unit_f64(rng).ln() / self.y_gamma.shape;
+            let log_max = log_x.max(log_y);
+            if log_max == f64::NEG_INFINITY {
+                // Both X and Y have underflowed even in the log domain, which can only happen
+                // for tiny shapes. The distribution is then as good as a Bernoulli distribution
+                // with p = alpha / (alpha + beta), which is its mean.
+                let alpha = self.x_gamma.shape;
+                return if random_unit_f64(rng) * (alpha + self.y_gamma.shape) < alpha {
+                    1.0
+                } else {
+                    0.0
+                };
+            }
+            let log_sum = log_max + ((log_x - log_max).exp() + (log_y - log_max).exp()).ln();
+            if log_sum <= 0.0 {
+                return (log_x - log_sum).exp();
+            }
+        }
+    }
+}
+
+impl<F: Float> Distribution<F> for Beta<F> {
+    fn sample(&self, rng: &mut impl Rng) -> F {
+        if self.x_gamma.shape < 1.0 && self.y_gamma.shape < 1.0 {
+            return F::from_f64(self.sample_johnk(rng));
+        }
+        // If X ~ Gamma(alpha) and Y ~ Gamma(beta), then X / (X + Y) ~ Beta(alpha, beta)
+        let x = self.x_gamma.sample(rng);
+        let y = self.y_gamma.sample(rng);
+        F::from_f64(x / (x + y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square_equal_bins;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    // The critical value for 9 degrees of freedom at p = 0.001
+    const CRITICAL_VALUE: f64 = 27.88;
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    fn assert_fits(distribution: impl Distribution<f64>, deciles: &[f64; 9]) {
+        let mut rng = rng();
+        let chi_square =
+            chi_square_equal_bins(distribution.sample_iter(&mut rng).take(100_000), deciles);
+        assert!(chi_square < CRITICAL_VALUE, "{chi_square}");
+    }
+
+    #[test]
+    fn gamma_fits_distribution() {
+        assert_fits(
+            Gamma::new(2.5, 1.0).unwrap(),
+            &[
+                0.8051539934811615,
+                1.1712671529205603,
+                1.499954066379953,
+                1.8277498115707929,
+                2.1757300955477636,
+                2.5659335372009107,
+                3.0322149920774524,
+                3.644638063324481,
+                4.618178449890559,
+            ],
+        );
+    }
+
+    #[test]
+    fn gamma_with_small_shape_fits_distribution() {
+        assert_fits(
+            Gamma::new(0.5, 1.0).unwrap(),
+            &[
+                0.007895387046715613,
+                0.03209237733365079,
+                0.07423593091627273,
+                0.13749794886422803,
+                0.2274682115597864,
+                0.3541631504003969,
+                0.5370970854287926,
+                0.8211872075749082,
+                1.3527717270477073,
+            ],
+        );
+    }
+
+    #[test]
+    fn gamma_scale() {
+        let mut rng1 = rng();
+        let mut rng2 = rng();
+        let gamma = Gamma::new(2.5, 1.0).unwrap();
+        let scaled_gamma = Gamma::new(2.5, 4.0).unwrap();
+        for _ in 0..100 {
+            let expected: f64 = rng2.sample(&gamma);
+            assert_eq!(rng1.sample(&scaled_gamma), expected * 4.0);
+        }
+    }
+
+    #[test]
+    fn gamma_with_tiny_shape_does_not_underflow_to_nan() {
+        let mut rng = rng();
+        let gamma = Gamma::new(1e-3, 1.0).unwrap();
+        assert!(gamma
+            .sample_iter(&mut rng)
+            .take(1000)
+            .all(|x: f64| x >= 0.0 && x.is_finite()));
+    }
+
+    #[test]
+    fn chi_squared_is_gamma() {
+        let mut rng1 = rng();
+        let mut rng2 = rng();
+        let chi_squared = ChiSquared::new(5.0).unwrap();
+        let gamma = Gamma::new(2.5, 2.0).unwrap();
+        for _ in 0..100 {
+            let expected: f64 = rng2.sample(&gamma);
+            assert_eq!(rng1.sample(&chi_squared), expected);
+        }
+    }
+
+    #[test]
+    fn beta_fits_distribution() {
+        assert_fits(
+            Beta::new(2.0, 5.0).unwrap(),
+            &[
+                0.09259525891312875,
+                0.13988068826995784,
+                0.18180347131894917,
+                0.22258353361542385,
+                0.26444998329565994,
+                0.3094444275453144,
+                0.36035769038002025,
+                0.42244752484627207,
+                0.5103163065514916,
+            ],
+        );
+    }
+
+    #[test]
+    fn beta_with_small_shapes_fits_distribution() {
+        assert_fits(
+            Beta::new(0.3, 0.7).unwrap(),
+            &[
+                0.0007720300685420433,
+                0.007768985045439308,
+                0.02986021572572563,
+                0.07702861881835601,
+                0.15877468697850164,
+                0.28194737607004616,
+                0.44767832988411144,
+                0.6470486286208954,
+                0.8540780751826775,
+            ],
+        );
+    }
+
+    #[test]
+    fn beta_with_tiny_shapes_is_0_or_1() {
+        let mut rng = rng();
+        let beta = Beta::new(1e-310, 3e-310).unwrap();
+        let samples: Vec<f64> = beta.sample_iter(&mut rng).take(1000).collect();
+        assert!(samples.iter().all(|&x| x == 0.0 || x == 1.0));
+        let ones = samples.iter().filter(|&&x| x == 1.0).count();
+        assert!((200..300).contains(&ones), "{ones}");
+    }
+
+    #[test]
+    fn beta_f32_is_in_unit_interval() {
+        let mut rng = rng();
+        let beta = Beta::new(0.5_f32, 0.5).unwrap();
+        assert!(beta
+            .sample_iter(&mut rng)
+            .take(1000)
+            .all(|x: f32| (0.0..=1.0).contains(&x)));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(Gamma::new(0.0, 1.0), Err(ParameterError::InvalidShape));
+        assert_eq!(Gamma::new(f64::NAN, 1.0), Err(ParameterError::InvalidShape));
+        assert_eq!(Gamma::new(1.0, -1.0), Err(ParameterError::InvalidScale));
+        assert_eq!(
+            Gamma::new(1.0_f32, f32::INFINITY),
+            Err(ParameterError::InvalidScale)
+        );
+        assert_eq!(
+            ChiSquared::new(-2.0),
+            Err(ParameterError::InvalidDegreesOfFreedom)
+        );
+        assert_eq!(
+            ChiSquared::new(f32::from_bits(1)),
+            Err(ParameterError::InvalidDegreesOfFreedom)
+        );
+        assert_eq!(Beta::new(1.0, 0.0), Err(ParameterError::InvalidShape));
+        assert_eq!(Beta::new(f64::NAN, 1.0), Err(ParameterError::InvalidShape));
+    }
+}
@@ -45,19 +45,63 @@
 //!
 //! It is fairly easy to write your own implementation of `EntropySource` for your platform.
 //!
+//! Random values of your own structs and enums can be generated by deriving [ValueFromRng],
+//! which requires the optional `derive` feature (this adds a dependency on the
+//! `smallrand-derive` crate and the crates it uses to parse Rust code):
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use smallrand::{SmallRng, ValueFromRng};
+//!
+//! #[derive(ValueFromRng)]
+//! enum Shape {
+//!     Circle { radius: f64 },
+//!     #[rng(weight = 2)]
+//!     Square(#[rng(range = 1..=10)] u32),
+//! }
+//!
+//! let mut rng = SmallRng::from_seed(42);
+//! let shape: Shape = rng.random();
+//! # }
+//! ```
+//!
+extern crate alloc;
 extern crate core;
+// Lets the code generated by the derive macro refer to this crate in tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as smallrand;
 
+mod alphabet;
 mod chacha;
+mod chacha_ietf;
+mod discrete;
+mod distributions;
 mod entropy;
+mod exponential;
+mod gamma;
 mod nonces;
+mod normal;
+mod permutation;
 mod ranges;
 mod rng;
+mod secrets;
 mod secure_entropy;
+mod seq;
 mod smallrng;
 mod stdrng;
+mod uniform;
+mod uuid;
+mod weighted;
 mod xoshiro;
+mod ziggurat_tables;
 
-pub use chacha::ChaCha12;
+pub use alphabet::{Alphabet, AlphabetError};
+pub use chacha::{ChaCha12, ChaCha20, ChaCha8, XChaCha12};
+pub use chacha_ietf::{ChaCha20Ietf, CounterOverflowError};
+pub use discrete::{Binomial, Geometric, Hypergeometric, Poisson};
+pub use distributions::{
+    Bernoulli, Distribution, Float, Open01, OpenClosed01, ParameterError, Standard,
+};
 #[cfg(feature = "std")]
 pub use entropy::DefaultEntropy;
 #[cfg(all(unix, feature = "std"))]
@@ -68,9 +112,19 @@ pub use entropy::GetRandom;
 #[cfg(feature = "std")]
 pub use entropy::HashMapEntropy;
 pub use entropy::SplitMix;
-pub use rng::Rng;
+pub use exponential::Exp;
+pub use gamma::{Beta, ChiSquared, Gamma};
+pub use normal::{LogNormal, Normal, StandardNormal};
+pub use permutation::Permutation;
+pub use rng::{CryptoRng, Rng, ValueFromRng};
+pub use secrets::{CharClass, PasswordError, PasswordGenerator, PasswordPolicy, TokenGenerator};
 #[cfg(feature = "std")]
 pub use secure_entropy::SecureEntropy;
+#[cfg(feature = "derive")]
+pub use smallrand_derive::ValueFromRng;
 pub use smallrng::SmallRng;
 pub use stdrng::StdRng;
+pub use uniform::Uniform;
+pub use uuid::{Uuid, UuidParseError, UuidV7Generator};
+pub use weighted::{DynamicWeightedIndex, Weight, WeightError, WeightedIndex};
 pub use xoshiro::Xoshiro256pp;
@@ -0,0 +1,302 @@
+use crate::distributions::{Distribution, Float, ParameterError};
+use crate::rng::{random_nonzero_unit_f64, random_unit_f64, Rng};
+use crate::ziggurat_tables::{NORM_F, NORM_R, NORM_X};
+
+/// The standard normal distribution, which has a mean of 0 and a standard deviation of 1.
+///
+/// Values are generated using the Ziggurat algorithm with 256 layers,
+/// and values in the tail are generated exactly using Marsaglia's tail algorithm.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StandardNormal;
+
+impl Distribution<f64> for StandardNormal {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        standard_normal(rng)
+    }
+}
+
+impl Distribution<f32> for StandardNormal {
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> f32 {
+        standard_normal(rng) as f32
+    }
+}
+
+/// Generates a value from the standard normal distribution.
+pub(crate) fn standard_normal(rng: &mut impl Rng) -> f64 {
+    // This is the Ziggurat algorithm of Marsaglia and Tsang (https://doi.org/10.18637/jss.v005.i08),
+    // with the improvements from Doornik (https://www.doornik.com/research/ziggurat.pdf).
+    // The magnitude is drawn using the full precision float generation of the crate,
+    // and the sign separately, so that values close to 0 keep their full precision.
+    loop {
+        let bits = rng.random_u32();
+        let layer = (bits & 0xff) as usize;
+        let x = random_unit_f64(rng) * NORM_X[layer];
+        let x = if x < NORM_X[layer + 1] {
+            // The point is inside the rectangle that is fully below the curve
+            x
+        } else if layer == 0 {
+            normal_tail(rng)
+        } else if NORM_F[layer] + (NORM_F[layer + 1] - NORM_F[layer]) * random_unit_f64(rng)
+            < (-0.5 * x * x).exp()
+        {
+            // The point is in the wedge between the rectangle and the curve, but below the curve
+            x
+        } else {
+            continue;
+        };
+        return if bits & 0x100 == 0 { x } else { -x };
+    }
+}
+
+/// Generates a value from the tail of the standard normal distribution (beyond R).
+fn normal_tail(rng: &mut impl Rng) -> f64 {
+    // Marsaglia's method, which is exact (https://doi.org/10.1214/aoms/1177703677)
+    loop {
+        let x = -random_nonzero_unit_f64(rng).ln() / NORM_R;
+        let y = -random_nonzero_unit_f64(rng).ln();
+        if 2.0 * y > x * x {
+            return NORM_R + x;
+        }
+    }
+}
+
+/// The normal (Gaussian) distribution, with a given mean and standard deviation.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Normal, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let normal = Normal::new(10.0, 2.0).unwrap();
+/// let value: f64 = rng.sample(&normal);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Normal<F> {
+    mean: F,
+    std_dev: F,
+}
+
+impl<F: Float> Normal<F> {
+    /// Creates a new normal distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `mean`: The mean of the distribution
+    /// * `std_dev`: The standard deviation of the distribution
+    ///
+    /// returns: [Normal], or an error if the parameters are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidMean] if `mean` is not finite, and
+    /// [ParameterError::InvalidStdDev] if `std_dev` is negative or not finite.
+    pub fn new(mean: F, std_dev: F) -> Result<Self, ParameterError> {
+        if !mean.to_f64().is_finite() {
+            return Err(ParameterError::InvalidMean);
+        }
+        if !(std_dev.to_f64().is_finite() && std_dev.to_f64() >= 0.0) {
+            return Err(ParameterError::InvalidStdDev);
+        }
+        Ok(Self { mean, std_dev })
+    }
+
+    /// Returns the mean of the distribution.
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+
+    /// Returns the standard deviation of the distribution.
+    pub fn std_dev(&self) -> F {
+        self.std_dev
+    }
+
+    #[inline]
+    fn sample_f64(&self, rng: &mut impl Rng) -> f64 {
+        self.mean.to_f64() + self.std_dev.to_f64() * standard_normal(rng)
+    }
+}
+
+impl<F: Float> Distribution<F> for Normal<F> {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> F {
+        F::from_f64(self.sample_f64(rng))
+    }
+}
+
+/// The log-normal distribution, which is the distribution of `exp(x)`
+/// where `x` is normally distributed.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{LogNormal, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let log_normal = LogNormal::new(0.0, 0.5).unwrap();
+/// let value: f64 = rng.sample(&log_normal);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LogNormal<F> {
+    normal: Normal<F>,
+}
+
+impl<F: Float> LogNormal<F> {
+    /// Creates a new log-normal distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `mu`: The mean of the underlying normal distribution
+    /// * `sigma`: The standard deviation of the underlying normal distribution
+    ///
+    /// returns: [LogNormal], or an error if the parameters are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParameterError::InvalidMean] if `mu` is not finite, and
+    /// [ParameterError::InvalidStdDev] if `sigma` is negative or not finite.
+    pub fn new(mu: F, sigma: F) -> Result<Self, ParameterError> {
+        Ok(Self {
+            normal: Normal::new(mu, sigma)?,
+        })
+    }
+}
+
+impl<F: Float> Distribution<F> for LogNormal<F> {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> F {
+        F::from_f64(self.normal.sample_f64(rng).exp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square_equal_bins;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    #[test]
+    fn standard_normal_fits_distribution() {
+        // These are the 5% quantiles of the standard normal distribution
+        const QUANTILES: [f64; 19] = [
+            -1.6448536269514726,
+            -1.2815515655446004,
+            -1.0364333894937896,
+            -0.8416212335729142,
+            -0.6744897501960817,
+            -0.5244005127080408,
+            -0.3853204664075676,
+            -0.2533471031357998,
+            -0.12566134685507405,
+            0.0,
+            0.12566134685507405,
+            0.2533471031357998,
+            0.3853204664075676,
+            0.5244005127080408,
+            0.6744897501960817,
+            0.8416212335729142,
+            1.0364333894937896,
+            1.2815515655446004,
+            1.6448536269514726,
+        ];
+        let mut rng = rng();
+        let chi_square =
+            chi_square_equal_bins((0..200_000).map(|_| standard_normal(&mut rng)), &QUANTILES);
+        // The critical value for 19 degrees of freedom at p = 0.001 is 43.82
+        assert!(chi_square < 43.82, "{chi_square}");
+    }
+
+    #[test]
+    fn tail_fits_distribution() {
+        // These are the 10% quantiles of the standard normal distribution,
+        // conditional on the value being larger than R
+        const QUANTILES: [f64; 9] = [
+            3.681100720075039,
+            3.711019757045983,
+            3.7446815313496016,
+            3.7832087022531433,
+            3.8283289574577246,
+            3.882911844689083,
+            3.952281052370193,
+            4.048229653322286,
+            4.2076566828459825,
+        ];
+        let mut rng = rng();
+        let chi_square =
+            chi_square_equal_bins((0..100_000).map(|_| normal_tail(&mut rng)), &QUANTILES);
+        // The critical value for 9 degrees of freedom at p = 0.001 is 27.88
+        assert!(chi_square < 27.88, "{chi_square}");
+    }
+
+    #[test]
+    fn tail_is_used() {
+        // The probability of a value beyond R is 2 * 1.29e-4
+        let mut rng = rng();
+        let count = (0..1_000_000)
+            .filter(|_| standard_normal(&mut rng).abs() > NORM_R)
+            .count();
+        assert!((200..320).contains(&count), "{count}");
+    }
+
+    #[test]
+    fn normal_mean_and_std_dev() {
+        let mut rng = rng();
+        let normal = Normal::new(10.0, 2.0).unwrap();
+        let values: Vec<f64> = normal.sample_iter(&mut rng).take(100_000).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance =
+            values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len() as f64;
+        assert!((mean - 10.0).abs() < 0.02, "{mean}");
+        assert!((variance.sqrt() - 2.0).abs() < 0.02, "{variance}");
+    }
+
+    #[test]
+    fn normal_f32() {
+        let mut rng = rng();
+        let normal = Normal::new(-5.0_f32, 0.5).unwrap();
+        let mean = normal.sample_iter(&mut rng).take(10_000).sum::<f32>() / 10_000.0;
+        assert!((mean + 5.0).abs() < 0.02, "{mean}");
+    }
+
+    #[test]
+    fn normal_with_zero_std_dev_is_constant() {
+        let mut rng = rng();
+        let normal = Normal::new(3.0, 0.0).unwrap();
+        assert!(normal.sample_iter(&mut rng).take(100).all(|v| v == 3.0));
+    }
+
+    #[test]
+    fn log_normal_is_exp_of_normal() {
+        let mut rng1 = rng();
+        let mut rng2 = rng();
+        let normal = Normal::new(1.0, 0.5).unwrap();
+        let log_normal = LogNormal::new(1.0, 0.5).unwrap();
+        for _ in 0..100 {
+            let expected: f64 = rng2.sample(&normal);
+            assert_eq!(rng1.sample(&log_normal), expected.exp());
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(Normal::new(f64::NAN, 1.0), Err(ParameterError::InvalidMean));
+        assert_eq!(
+            Normal::new(f64::INFINITY, 1.0),
+            Err(ParameterError::InvalidMean)
+        );
+        assert_eq!(Normal::new(0.0, -1.0), Err(ParameterError::InvalidStdDev));
+        assert_eq!(
+            Normal::new(0.0, f32::NAN),
+            Err(ParameterError::InvalidStdDev)
+        );
+        assert_eq!(
+            LogNormal::new(0.0, f64::INFINITY),
+            Err(ParameterError::InvalidStdDev)
+        );
+    }
+}
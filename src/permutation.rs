@@ -0,0 +1,172 @@
+use crate::rng::Rng;
+use core::iter::FusedIterator;
+
+/// An iterator that outputs the numbers `0..n` in random order, without allocating memory.
+///
+/// The order is a pseudorandom permutation: Each position is mapped to a number
+/// by a Feistel network keyed with random values from the [Rng] that created it,
+/// and numbers outside the range are skipped by "cycle walking".
+/// This means that every permutation is not equally likely, as the number of
+/// possible permutations is far larger than the number of keys for large `n`,
+/// but the order cannot be told apart from a uniformly random one without the key.
+/// Use [Rng::shuffle](crate::Rng::shuffle()) if an exactly uniform permutation is needed.
+///
+/// Created by [Rng::permutation](crate::Rng::permutation()).
+#[derive(Clone, Debug)]
+pub struct Permutation {
+    keys: [u64; Self::ROUNDS],
+    // The Feistel network works on numbers of twice this many bits
+    half_bits: u32,
+    length: usize,
+    position: usize,
+}
+
+impl Permutation {
+    const ROUNDS: usize = 6;
+
+    pub(crate) fn new(rng: &mut impl Rng, length: usize) -> Self {
+        let mut keys = [0; Self::ROUNDS];
+        for key in &mut keys {
+            *key = rng.random_u64();
+        }
+        // The Feistel network covers less than four times the length,
+        // so that cycle walking takes few steps
+        let bits = usize::BITS - length.saturating_sub(1).leading_zeros();
+        Self {
+            keys,
+            half_bits: bits.div_ceil(2).max(1),
+            length,
+            position: 0,
+        }
+    }
+
+    /// Returns the number at a given position of the permutation.
+    fn permute(&self, position: usize) -> usize {
+        // Applying the Feistel network repeatedly must eventually lead back into the
+        // range, since the network is a permutation and the position is in the range
+        let mut value = position as u64;
+        loop {
+            value = self.feistel(value);
+            if let Ok(value) = usize::try_from(value) {
+                if value < self.length {
+                    return value;
+                }
+            }
+        }
+    }
+
+    fn feistel(&self, value: u64) -> u64 {
+        let mask = (1_u64 << self.half_bits) - 1;
+        let mut left = value >> self.half_bits;
+        let mut right = value & mask;
+        for key in self.keys {
+            (left, right) = (right, left ^ (mix(right ^ key) & mask));
+        }
+        (left << self.half_bits) | right
+    }
+}
+
+/// The mixing function of SplitMix64, which is used as the round function.
+fn mix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl Iterator for Permutation {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        if self.position >= self.length {
+            return None;
+        }
+        let value = self.permute(self.position);
+        self.position += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.position;
+        (remaining, Some(remaining))
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<usize> {
+        // Any position can be calculated directly
+        self.position = self.position.saturating_add(n).min(self.length);
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for Permutation {}
+
+impl FusedIterator for Permutation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    #[test]
+    fn all_numbers_are_output_once() {
+        let mut rng = rng();
+        for length in [0, 1, 2, 3, 4, 5, 17, 1000, (1 << 16) + 3] {
+            let mut seen = vec![false; length];
+            let permutation = Permutation::new(&mut rng, length);
+            assert_eq!(permutation.len(), length);
+            for value in permutation {
+                assert!(!seen[value]);
+                seen[value] = true;
+            }
+            assert!(seen.iter().all(|seen| *seen));
+        }
+    }
+
+    #[test]
+    fn order_is_random() {
+        // The first number should be uniformly distributed, and so should the
+        // number that follows 0
+        let mut rng = rng();
+        let mut first_count = [0; 10];
+        let mut next_count = [0; 10];
+        for _ in 0..10_000 {
+            let permutation: Vec<usize> = Permutation::new(&mut rng, 10).collect();
+            first_count[permutation[0]] += 1;
+            let zero_position = permutation.iter().position(|v| *v == 0).unwrap();
+            next_count[permutation[(zero_position + 1) % 10]] += 1;
+        }
+        // The critical values for 9 and 8 degrees of freedom at p = 0.001 are 27.88 and 26.12
+        let first_chi_square = chi_square(&first_count, &[1000.0; 10]);
+        assert!(first_chi_square < 27.88, "{first_chi_square}");
+        let next_chi_square = chi_square(&next_count[1..], &[10_000.0 / 9.0; 9]);
+        assert!(next_chi_square < 26.12, "{next_chi_square}");
+    }
+
+    #[test]
+    fn nth_skips_positions() {
+        let mut rng = rng();
+        let permutation = Permutation::new(&mut rng, 100);
+        let all: Vec<usize> = permutation.clone().collect();
+        let mut skipping = permutation;
+        assert_eq!(skipping.nth(10), Some(all[10]));
+        assert_eq!(skipping.next(), Some(all[11]));
+        assert_eq!(skipping.len(), 88);
+        assert_eq!(skipping.nth(100), None);
+        assert_eq!(skipping.next(), None);
+    }
+
+    #[test]
+    fn huge_permutation() {
+        let mut rng = rng();
+        let permutation = Permutation::new(&mut rng, usize::MAX);
+        assert!(permutation.take(100).all(|value| value < usize::MAX));
+    }
+}
@@ -1,4 +1,7 @@
-use core::ops::{Bound, Range, RangeBounds};
+use core::ops::{Bound, RangeBounds};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{Instant, SystemTime};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct GenerateRange<T> {
@@ -46,22 +49,248 @@ from_range_bounds!(i64);
 from_range_bounds!(i128);
 from_range_bounds!(isize);
 
-macro_rules! from_range {
+/// Steps to the neighbouring floats, as `f64::next_up` and `f64::next_down`
+/// are not available in all supported Rust versions.
+pub(crate) trait NextFloat {
+    /// Returns the smallest float that is larger than this one.
+    fn next_above(self) -> Self;
+
+    /// Returns the largest float that is smaller than this one.
+    fn next_below(self) -> Self;
+}
+
+macro_rules! next_float {
+    ($value_type: ty) => {
+        impl NextFloat for $value_type {
+            #[inline]
+            fn next_above(self) -> Self {
+                if self.is_nan() || self == <$value_type>::INFINITY {
+                    self
+                } else if self == 0.0 {
+                    <$value_type>::from_bits(1)
+                } else if self > 0.0 {
+                    <$value_type>::from_bits(self.to_bits() + 1)
+                } else {
+                    <$value_type>::from_bits(self.to_bits() - 1)
+                }
+            }
+
+            #[inline]
+            fn next_below(self) -> Self {
+                -(-self).next_above()
+            }
+        }
+    };
+}
+
+next_float!(f32);
+next_float!(f64);
+
+// Float ranges are converted to inclusive ranges just like integer ranges,
+// by stepping to the neighbouring float of an excluded bound.
+macro_rules! from_float_range_bounds {
     ($value_type: ty) => {
-        impl From<Range<$value_type>> for GenerateRange<$value_type> {
+        impl<R> From<R> for GenerateRange<$value_type>
+        where
+            R: RangeBounds<$value_type>,
+        {
             #[inline(always)]
-            fn from(range: Range<$value_type>) -> Self {
+            fn from(range: R) -> Self {
+                Self {
+                    start: match range.start_bound() {
+                        Bound::Included(start) => finite(*start),
+                        Bound::Excluded(start) => finite(*start).next_above(),
+                        Bound::Unbounded => <$value_type>::MIN,
+                    },
+                    end_inclusive: match range.end_bound() {
+                        Bound::Included(end) => finite(*end),
+                        Bound::Excluded(end) => finite(*end).next_below(),
+                        Bound::Unbounded => <$value_type>::MAX,
+                    },
+                }
+            }
+        }
+    };
+}
+
+from_float_range_bounds!(f32);
+from_float_range_bounds!(f64);
+
+#[inline]
+fn finite<T: Into<f64> + Copy>(bound: T) -> T {
+    assert!(bound.into().is_finite(), "Range bounds must be finite");
+    bound
+}
+
+// The valid chars are all the Unicode scalar values, which are the code points
+// outside the surrogate range. Char ranges are generated as ranges of indexes
+// that skip the surrogates, so that every char in the range is equally likely.
+const SURROGATES_START: u32 = 0xD800;
+const SURROGATES_LENGTH: u32 = 0x800;
+
+/// The number of valid chars.
+pub(crate) const CHAR_COUNT: u32 = char::MAX as u32 + 1 - SURROGATES_LENGTH;
+
+/// Returns the index of a char among all the valid chars.
+#[inline]
+pub(crate) fn char_to_index(value: char) -> u32 {
+    let value = u32::from(value);
+    if value >= SURROGATES_START {
+        value - SURROGATES_LENGTH
+    } else {
+        value
+    }
+}
+
+/// Returns the char with an index below [CHAR_COUNT].
+#[inline]
+pub(crate) fn index_to_char(index: u32) -> char {
+    let value = if index >= SURROGATES_START {
+        index + SURROGATES_LENGTH
+    } else {
+        index
+    };
+    char::from_u32(value).expect("Char index out of range")
+}
+
+impl<R> From<R> for GenerateRange<char>
+where
+    R: RangeBounds<char>,
+{
+    #[inline]
+    fn from(range: R) -> Self {
+        Self {
+            start: match range.start_bound() {
+                Bound::Included(start) => *start,
+                Bound::Excluded(start) => {
+                    let index = char_to_index(*start) + 1;
+                    assert!(index < CHAR_COUNT, "Range start overflow");
+                    index_to_char(index)
+                }
+                Bound::Unbounded => '\0',
+            },
+            end_inclusive: match range.end_bound() {
+                Bound::Included(end) => *end,
+                Bound::Excluded(end) => index_to_char(
+                    char_to_index(*end)
+                        .checked_sub(1)
+                        .expect("Range end underflow"),
+                ),
+                Bound::Unbounded => char::MAX,
+            },
+        }
+    }
+}
+
+impl<R> From<R> for GenerateRange<Duration>
+where
+    R: RangeBounds<Duration>,
+{
+    #[inline]
+    fn from(range: R) -> Self {
+        const NANOSECOND: Duration = Duration::from_nanos(1);
+        Self {
+            start: match range.start_bound() {
+                Bound::Included(start) => *start,
+                Bound::Excluded(start) => {
+                    start.checked_add(NANOSECOND).expect("Range start overflow")
+                }
+                Bound::Unbounded => Duration::ZERO,
+            },
+            end_inclusive: match range.end_bound() {
+                Bound::Included(end) => *end,
+                Bound::Excluded(end) => end.checked_sub(NANOSECOND).expect("Range end underflow"),
+                Bound::Unbounded => Duration::MAX,
+            },
+        }
+    }
+}
+
+/// Converts a number of nanoseconds to a [Duration].
+#[allow(clippy::cast_possible_truncation)]
+#[inline]
+pub(crate) fn duration_from_nanos(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
+
+/// The points in time that ranges are supported for, which are [SystemTime] and [Instant].
+#[cfg(feature = "std")]
+pub(crate) trait TimePoint: Copy {
+    /// Returns the time from an earlier point in time to this one,
+    /// or None if the other point in time is later.
+    fn duration_after(self, earlier: Self) -> Option<Duration>;
+
+    /// Returns the point in time a duration later, or None if it cannot be represented.
+    fn add_duration(self, duration: Duration) -> Option<Self>;
+}
+
+#[cfg(feature = "std")]
+impl TimePoint for SystemTime {
+    fn duration_after(self, earlier: Self) -> Option<Duration> {
+        self.duration_since(earlier).ok()
+    }
+
+    fn add_duration(self, duration: Duration) -> Option<Self> {
+        self.checked_add(duration)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TimePoint for Instant {
+    fn duration_after(self, earlier: Self) -> Option<Duration> {
+        self.checked_duration_since(earlier)
+    }
+
+    fn add_duration(self, duration: Duration) -> Option<Self> {
+        self.checked_add(duration)
+    }
+}
+
+// Time ranges must be bounded, as there is no earliest or latest point in time.
+// The clocks of some platforms have a resolution coarser than a nanosecond, so an excluded
+// end is found by going forward from the start, as stepping back from the end
+// could be rounded to the end itself.
+macro_rules! from_time_range_bounds {
+    ($value_type: ty) => {
+        #[cfg(feature = "std")]
+        impl<R> From<R> for GenerateRange<$value_type>
+        where
+            R: RangeBounds<$value_type>,
+        {
+            #[inline]
+            fn from(range: R) -> Self {
+                const NANOSECOND: Duration = Duration::from_nanos(1);
+                let start = match range.start_bound() {
+                    Bound::Included(start) => *start,
+                    Bound::Excluded(start) => start
+                        .add_duration(NANOSECOND)
+                        .expect("Range start overflow"),
+                    Bound::Unbounded => panic!("Time ranges must have a start"),
+                };
+                let end_inclusive = match range.end_bound() {
+                    Bound::Included(end) => *end,
+                    Bound::Excluded(end) => end
+                        .duration_after(start)
+                        .and_then(|span| span.checked_sub(NANOSECOND))
+                        .and_then(|span| start.add_duration(span))
+                        .expect("Inverted range"),
+                    Bound::Unbounded => panic!("Time ranges must have an end"),
+                };
                 Self {
-                    start: range.start,
-                    end_inclusive: range.end,
+                    start,
+                    end_inclusive,
                 }
             }
         }
     };
 }
 
-from_range!(f32);
-from_range!(f64);
+from_time_range_bounds!(SystemTime);
+from_time_range_bounds!(Instant);
 
 #[cfg(test)]
 mod test {
@@ -98,16 +327,165 @@ mod test {
 
     #[test]
     fn float_conversions() {
-        fn to_range(range: Range<f64>) -> GenerateRange<f64> {
+        fn to_range(range: impl RangeBounds<f64>) -> GenerateRange<f64> {
             range.into()
         }
 
         assert_eq!(
             GenerateRange {
                 start: 2_f64,
-                end_inclusive: 42_f64
+                end_inclusive: 42_f64 - 32.0 * f64::EPSILON
             },
             to_range(2.0..42.0)
         );
+        assert_eq!(
+            GenerateRange {
+                start: 2_f64,
+                end_inclusive: 42_f64
+            },
+            to_range(2.0..=42.0)
+        );
+        assert_eq!(
+            GenerateRange {
+                start: f64::MIN,
+                end_inclusive: f64::MAX
+            },
+            to_range(..)
+        );
+        assert_eq!(
+            GenerateRange {
+                start: f64::from_bits(1),
+                end_inclusive: -f64::from_bits(1)
+            },
+            to_range((Bound::Excluded(0.0), Bound::Excluded(0.0)))
+        );
+    }
+
+    #[test]
+    fn next_float() {
+        assert_eq!(1_f64.next_above(), 1.0 + f64::EPSILON);
+        assert_eq!(1_f64.next_below(), 1.0 - f64::EPSILON / 2.0);
+        assert_eq!((-1_f32).next_above(), -1.0 + f32::EPSILON / 2.0);
+        assert_eq!((-0_f32).next_above(), f32::from_bits(1));
+        assert_eq!(f64::MAX.next_above(), f64::INFINITY);
+        assert_eq!(f64::INFINITY.next_below(), f64::MAX);
+        assert!(f32::NAN.next_above().is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "Range bounds must be finite")]
+    fn nan_bound_panics() {
+        let _ = GenerateRange::from(0.0..f64::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range bounds must be finite")]
+    fn infinite_bound_panics() {
+        let _ = GenerateRange::from(f32::NEG_INFINITY..=0.0);
+    }
+
+    #[test]
+    fn char_conversions() {
+        fn to_range(range: impl RangeBounds<char>) -> GenerateRange<char> {
+            range.into()
+        }
+
+        assert_eq!(
+            GenerateRange {
+                start: 'a',
+                end_inclusive: 'x'
+            },
+            to_range('a'..'y')
+        );
+        assert_eq!(
+            GenerateRange {
+                start: '\u{e000}',
+                end_inclusive: '\u{d7ff}'
+            },
+            to_range((Bound::Excluded('\u{d7ff}'), Bound::Excluded('\u{e000}')))
+        );
+        assert_eq!(
+            GenerateRange {
+                start: '\0',
+                end_inclusive: char::MAX
+            },
+            to_range(..)
+        );
+    }
+
+    #[test]
+    fn char_indexes_skip_surrogates() {
+        assert_eq!(char_to_index('\u{d7ff}') + 1, char_to_index('\u{e000}'));
+        assert_eq!(char_to_index(char::MAX), CHAR_COUNT - 1);
+        for value in ['\0', 'a', '\u{d7ff}', '\u{e000}', '\u{1f600}', char::MAX] {
+            assert_eq!(index_to_char(char_to_index(value)), value);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Range end underflow")]
+    fn char_range_end_underflow_panics() {
+        let _ = GenerateRange::from(..'\0');
+    }
+
+    #[test]
+    fn duration_conversions() {
+        fn to_range(range: impl RangeBounds<Duration>) -> GenerateRange<Duration> {
+            range.into()
+        }
+
+        assert_eq!(
+            GenerateRange {
+                start: Duration::from_secs(1),
+                end_inclusive: Duration::new(1, 999_999_999)
+            },
+            to_range(Duration::from_secs(1)..Duration::from_secs(2))
+        );
+        assert_eq!(
+            GenerateRange {
+                start: Duration::ZERO,
+                end_inclusive: Duration::MAX
+            },
+            to_range(..)
+        );
+        assert_eq!(duration_from_nanos(Duration::MAX.as_nanos()), Duration::MAX);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn time_conversions() {
+        let start = SystemTime::UNIX_EPOCH;
+        let end = start + Duration::from_secs(1);
+        assert_eq!(
+            GenerateRange {
+                start,
+                end_inclusive: start + Duration::from_nanos(999_999_999)
+            },
+            GenerateRange::from(start..end)
+        );
+        let start = Instant::now();
+        let end = start + Duration::from_secs(1);
+        assert_eq!(
+            GenerateRange {
+                start,
+                end_inclusive: end
+            },
+            GenerateRange::from(start..=end)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "Inverted range")]
+    fn empty_time_range_panics() {
+        let now = Instant::now();
+        let _ = GenerateRange::from(now..now);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "Time ranges must have an end")]
+    fn unbounded_time_range_panics() {
+        let _ = GenerateRange::from(SystemTime::UNIX_EPOCH..);
     }
 }
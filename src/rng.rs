@@ -1,7 +1,22 @@
 #![allow(clippy::module_name_repetitions)]
 
-use crate::ranges::GenerateRange;
+use crate::alphabet::Alphabet;
+use crate::distributions::Distribution;
+use crate::permutation::Permutation;
+#[cfg(feature = "std")]
+use crate::ranges::TimePoint;
+use crate::ranges::{char_to_index, duration_from_nanos, index_to_char, GenerateRange, CHAR_COUNT};
+use crate::seq::{choose_multiple_iter, sample_indices};
+use crate::uniform::{IntSampler, SampleUniform};
+use crate::weighted::{choose_multiple_weighted, choose_weighted, Weight, WeightError};
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::mem;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+use core::time::Duration;
 
 /// This is the trait that all PRNGs must implement.
 /// It declares two functions that PRNGs must implement (to generate u32 and u64 random values),
@@ -16,11 +31,16 @@ pub trait Rng {
     /// Used by other functions as input.
     fn random_u64(&mut self) -> u64;
 
-    /// Generates a single random integer or bool.
+    /// Generates a single random integer, bool, float or char,
+    /// or an array, tuple, `Option`, `Wrapping` or non-zero integer made from them.
+    /// Integers are uniformly distributed over all possible values of the type,
+    /// floats are uniformly distributed in [0, 1),
+    /// chars are uniformly distributed over all valid Unicode scalar values,
+    /// and `Option` is `None` with probability 1/2.
     ///
     /// # Arguments
     ///
-    /// returns: A random unsigned integer
+    /// returns: A random value
     ///
     #[inline]
     fn random<T>(&mut self) -> T
@@ -31,12 +51,19 @@ pub trait Rng {
         T::value_from_rng(self)
     }
 
-    /// Generates a single random integer or float in a specified range.
+    /// Generates a single random integer, float, char, duration or point in time
+    /// in a specified range.
     /// The distribution is strictly uniform.
     /// The following types are supported:
-    /// u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+    /// u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, char, `Duration`,
+    /// and `SystemTime` and `Instant` when `std` is enabled.
     ///
-    /// Any kind of range is supported for integers, but only `Range` for floats.
+    /// Any kind of range is supported, except unbounded ranges of points in time.
+    /// Unbounded float ranges extend to the smallest or largest finite value of the type,
+    /// and the end of an inclusive float range is a possible outcome.
+    /// Because of rounding, the end can be up to about twice as likely
+    /// as the other values of the range.
+    /// Durations and points in time have a resolution of one nanosecond.
     ///
     /// # Arguments
     ///
@@ -44,6 +71,11 @@ pub trait Rng {
     ///
     /// returns: A random value in the range
     ///
+    /// # Panics
+    ///
+    /// Panics if the range is inverted or empty, if a float bound is NaN or infinite,
+    /// or if a range of points in time is unbounded.
+    ///
     fn range<T>(&mut self, range: impl Into<GenerateRange<T>>) -> T
     where
         T: RangeFromRng,
@@ -52,6 +84,23 @@ pub trait Rng {
         T::range_from_rng(self, range)
     }
 
+    /// Draws a single value from a [Distribution].
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution`: The distribution to sample from
+    ///
+    /// returns: A value drawn from the distribution
+    ///
+    #[inline]
+    fn sample<T, D>(&mut self, distribution: &D) -> T
+    where
+        D: Distribution<T>,
+        Self: Sized,
+    {
+        distribution.sample(self)
+    }
+
     /// Provides an iterator that emits random values.
     ///
     /// returns: An iterator that outputs random values. Never None.
@@ -105,6 +154,47 @@ pub trait Rng {
         }
     }
 
+    /// Generates a random string of characters from an [Alphabet].
+    /// Every character of the alphabet is equally likely at each position.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The number of characters to generate
+    /// * `alphabet`: The characters to pick from
+    ///
+    /// returns: A random string
+    ///
+    #[inline]
+    fn string(&mut self, length: usize, alphabet: &Alphabet) -> String
+    where
+        Self: Sized,
+    {
+        let mut string = String::with_capacity(length);
+        alphabet.generate(self, length, |c| string.push(c));
+        string
+    }
+
+    /// Fills a mutable slice of chars with random characters from an [Alphabet].
+    /// Every character of the alphabet is equally likely at each position.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination`: The slice to fill
+    /// * `alphabet`: The characters to pick from
+    ///
+    #[inline]
+    fn fill_chars(&mut self, destination: &mut [char], alphabet: &Alphabet)
+    where
+        Self: Sized,
+    {
+        let mut elements = destination.iter_mut();
+        alphabet.generate(self, elements.len(), |c| {
+            if let Some(element) = elements.next() {
+                *element = c;
+            }
+        });
+    }
+
     /// Shuffles the elements of a slice
     ///
     /// # Arguments
@@ -126,10 +216,299 @@ pub trait Rng {
             }
         }
     }
+
+    /// Shuffles the first elements of a slice, by moving randomly picked elements there.
+    /// This is faster than shuffling the whole slice if only a few elements are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: The slice to shuffle
+    /// * `amount`: The number of elements to shuffle.
+    ///   The whole slice is shuffled if this is larger than its length.
+    ///
+    /// returns: The shuffled elements, and the remaining elements (in no particular order)
+    ///
+    fn partial_shuffle<'a, T>(
+        &mut self,
+        target: &'a mut [T],
+        amount: usize,
+    ) -> (&'a mut [T], &'a mut [T])
+    where
+        Self: Sized,
+    {
+        let amount = amount.min(target.len());
+        // This is the first steps of the same algorithm as in shuffle.
+        // The last element does not need to be swapped with itself, so this gives
+        // the same result as shuffle if the whole slice is shuffled.
+        for inx in 0..amount.min(target.len().saturating_sub(1)) {
+            target.swap(inx, self.range(inx..target.len()));
+        }
+        target.split_at_mut(amount)
+    }
+
+    /// Provides an iterator that outputs the numbers `0..length` in a random order,
+    /// without allocating memory for them.
+    ///
+    /// The order is pseudorandom, see [Permutation] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The number of numbers to output
+    ///
+    /// returns: An iterator that outputs each of the numbers once
+    ///
+    fn permutation(&mut self, length: usize) -> Permutation
+    where
+        Self: Sized,
+    {
+        Permutation::new(self, length)
+    }
+
+    /// Picks a random element of a slice, with equal probability for all elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The slice to pick from
+    ///
+    /// returns: A reference to the picked element, or None if the slice is empty
+    ///
+    #[inline]
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T>
+    where
+        Self: Sized,
+    {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.range(0..items.len())])
+        }
+    }
+
+    /// Picks a random element of a mutable slice, with equal probability for all elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The slice to pick from
+    ///
+    /// returns: A mutable reference to the picked element, or None if the slice is empty
+    ///
+    #[inline]
+    fn choose_mut<'a, T>(&mut self, items: &'a mut [T]) -> Option<&'a mut T>
+    where
+        Self: Sized,
+    {
+        if items.is_empty() {
+            None
+        } else {
+            let inx = self.range(0..items.len());
+            Some(&mut items[inx])
+        }
+    }
+
+    /// Picks a random element from an iterator, with equal probability for all elements.
+    /// The iterator is only traversed once, and its length does not need to be known.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The iterator to pick from
+    ///
+    /// returns: The picked element, or None if the iterator is empty
+    ///
+    fn choose_iter<I>(&mut self, items: I) -> Option<I::Item>
+    where
+        I: IntoIterator,
+        Self: Sized,
+    {
+        let mut items = items.into_iter();
+        if let (lower, Some(upper)) = items.size_hint() {
+            // The length is known, so the element can be picked directly
+            if lower == upper {
+                return if lower == 0 {
+                    None
+                } else {
+                    items.nth(self.range(0..lower))
+                };
+            }
+        }
+        // This is reservoir sampling with a reservoir of one element:
+        // The n-th element replaces the picked one with probability 1/n
+        let mut picked = items.next()?;
+        for (count, item) in (2_usize..).zip(items) {
+            if self.range(0..count) == 0 {
+                picked = item;
+            }
+        }
+        Some(picked)
+    }
+
+    /// Picks a number of distinct indexes from `0..length`, in random order.
+    /// All possible selections are equally likely.
+    ///
+    /// Floyd's algorithm is used if few of the indexes are picked, so that the memory used
+    /// does not depend on `length`. Otherwise, a partial Fisher-Yates shuffle is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The number of indexes to pick from
+    /// * `amount`: The number of indexes to pick
+    ///
+    /// returns: The picked indexes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is larger than `length`.
+    fn sample_indices(&mut self, length: usize, amount: usize) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        sample_indices(self, length, amount)
+    }
+
+    /// Picks a number of distinct elements of a slice, in random order.
+    /// All possible selections are equally likely.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The slice to pick from
+    /// * `amount`: The number of elements to pick.
+    ///   All the elements are picked if this is larger than the length of the slice.
+    ///
+    /// returns: References to the picked elements
+    ///
+    fn choose_multiple<'a, T>(&mut self, items: &'a [T], amount: usize) -> Vec<&'a T>
+    where
+        Self: Sized,
+    {
+        sample_indices(self, items.len(), amount.min(items.len()))
+            .into_iter()
+            .map(|inx| &items[inx])
+            .collect()
+    }
+
+    /// Picks a number of distinct elements from an iterator, in random order.
+    /// All possible selections are equally likely.
+    /// The iterator is only traversed once, and its length does not need to be known.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The iterator to pick from
+    /// * `amount`: The number of elements to pick.
+    ///   All the elements are picked if this is larger than the number of elements.
+    ///
+    /// returns: The picked elements
+    ///
+    fn choose_multiple_iter<I>(&mut self, items: I, amount: usize) -> Vec<I::Item>
+    where
+        I: IntoIterator,
+        Self: Sized,
+    {
+        choose_multiple_iter(self, items, amount)
+    }
+
+    /// Picks one item from a slice, with a probability proportional to its weight.
+    /// This takes O(n) time, so use [WeightedIndex](crate::WeightedIndex) instead
+    /// if many items are to be picked using the same weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The items to pick from
+    /// * `weight`: A function that returns the weight of an item.
+    ///   The weights can be integers up to 64 bits, f32 or f64.
+    ///
+    /// returns: A reference to the picked item, or an error if the weights are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns a [WeightError] if there are no items, or if the weights are negative,
+    /// NaN, infinite, all zero or their sum is too large.
+    fn choose_weighted<'a, T, W>(
+        &mut self,
+        items: &'a [T],
+        weight: impl Fn(&T) -> W,
+    ) -> Result<&'a T, WeightError>
+    where
+        W: Weight,
+        Self: Sized,
+    {
+        choose_weighted(self, items, weight)
+    }
+
+    /// Picks a number of distinct indexes of a slice of weights, with probabilities
+    /// proportional to the weights. This takes O(n log k) time.
+    ///
+    /// The indexes are returned in the order that they would have been picked in
+    /// if they were picked one at a time, each time among the indexes not picked yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights`: The weights of the indexes, which can be integers up to 64 bits, f32 or f64.
+    ///   Indexes with a weight of zero are never picked.
+    /// * `amount`: The number of indexes to pick
+    ///
+    /// returns: The picked indexes, or an error if the weights are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns a [WeightError] if a weight is negative, NaN or infinite,
+    /// or if fewer than `amount` weights are non-zero.
+    fn choose_multiple_weighted<W>(
+        &mut self,
+        weights: &[W],
+        amount: usize,
+    ) -> Result<Vec<usize>, WeightError>
+    where
+        W: Weight,
+        Self: Sized,
+    {
+        choose_multiple_weighted(self, weights, amount)
+    }
 }
 
+/// A marker trait for the random generators that are suitable for generating secrets,
+/// like passwords, keys and tokens, because their output cannot be predicted
+/// from earlier output.
+///
+/// It is implemented for [StdRng](crate::StdRng) and the ChaCha generators,
+/// like [ChaCha12](crate::ChaCha12) and [XChaCha12](crate::XChaCha12),
+/// but not for fast generators like [SmallRng](crate::SmallRng).
+/// The functions that generate secrets require it:
+///
+/// ```compile_fail
+/// use smallrand::{SmallRng, TokenGenerator, Alphabet};
+/// let mut rng = SmallRng::from_seed(42);
+/// let token = TokenGenerator::new(Alphabet::BASE64URL, 32).generate(&mut rng);
+/// ```
+pub trait CryptoRng: Rng {}
+
+/// This is the trait for the types that [Rng::random] can generate.
+///
+/// It can be derived for structs and enums with the `derive` feature.
+/// See the crate documentation for an example.
 pub trait ValueFromRng {
+    /// Generates a random value.
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy_source`: The random generator to use
+    ///
+    /// returns: A random value
     fn value_from_rng<T: Rng>(entropy_source: &mut T) -> Self;
+
+    /// Generates an array of random values.
+    /// Types can override this if they have a faster way than generating one value at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use
+    ///
+    /// returns: An array of random values
+    #[inline]
+    fn array_from_rng<T: Rng, const N: usize>(rng: &mut T) -> [Self; N]
+    where
+        Self: Sized,
+    {
+        core::array::from_fn(|_| Self::value_from_rng(rng))
+    }
 }
 
 impl ValueFromRng for bool {
@@ -139,12 +518,40 @@ impl ValueFromRng for bool {
     }
 }
 
+impl ValueFromRng for char {
+    #[inline]
+    fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+        index_to_char(rng.range(0..CHAR_COUNT))
+    }
+}
+
+impl ValueFromRng for f32 {
+    #[inline]
+    fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+        random_unit_f32(rng)
+    }
+}
+
+impl ValueFromRng for f64 {
+    #[inline]
+    fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+        random_unit_f64(rng)
+    }
+}
+
 impl ValueFromRng for u8 {
     #[allow(clippy::cast_possible_truncation)]
     #[inline]
     fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
         rng.random_u32() as Self
     }
+
+    #[inline]
+    fn array_from_rng<T: Rng, const N: usize>(rng: &mut T) -> [Self; N] {
+        let mut array = [0; N];
+        rng.fill_u8(&mut array);
+        array
+    }
 }
 
 impl ValueFromRng for u16 {
@@ -220,6 +627,79 @@ value_from_rng_signed!(i64, u64);
 value_from_rng_signed!(i128, u128);
 value_from_rng_signed!(isize, usize);
 
+impl<V: ValueFromRng, const N: usize> ValueFromRng for [V; N] {
+    #[inline]
+    fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+        V::array_from_rng(rng)
+    }
+}
+
+// Macro to implement ValueFromRng for tuples of all lengths up to the number of type parameters:
+macro_rules! value_from_rng_tuple {
+    () => {};
+    ($first: ident $(, $rest: ident)*) => {
+        impl<$first: ValueFromRng, $($rest: ValueFromRng),*> ValueFromRng for ($first, $($rest,)*) {
+            #[inline]
+            fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+                ($first::value_from_rng(rng), $($rest::value_from_rng(rng),)*)
+            }
+        }
+
+        value_from_rng_tuple!($($rest),*);
+    };
+}
+
+value_from_rng_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Generates None and Some with equal probability.
+impl<V: ValueFromRng> ValueFromRng for Option<V> {
+    #[inline]
+    fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+        if rng.random::<bool>() {
+            Some(V::value_from_rng(rng))
+        } else {
+            None
+        }
+    }
+}
+
+impl<V: ValueFromRng> ValueFromRng for Wrapping<V> {
+    #[inline]
+    fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+        Wrapping(V::value_from_rng(rng))
+    }
+}
+
+// Macro to implement ValueFromRng for non-zero types.
+// Zero is rejected, which keeps the other values equally likely.
+macro_rules! value_from_rng_non_zero {
+    ($output_type: ty, $source_type: ty) => {
+        impl ValueFromRng for $output_type {
+            #[inline]
+            fn value_from_rng<T: Rng>(rng: &mut T) -> Self {
+                loop {
+                    if let Some(value) = <$output_type>::new(<$source_type>::value_from_rng(rng)) {
+                        return value;
+                    }
+                }
+            }
+        }
+    };
+}
+
+value_from_rng_non_zero!(NonZeroU8, u8);
+value_from_rng_non_zero!(NonZeroU16, u16);
+value_from_rng_non_zero!(NonZeroU32, u32);
+value_from_rng_non_zero!(NonZeroU64, u64);
+value_from_rng_non_zero!(NonZeroU128, u128);
+value_from_rng_non_zero!(NonZeroUsize, usize);
+value_from_rng_non_zero!(NonZeroI8, i8);
+value_from_rng_non_zero!(NonZeroI16, i16);
+value_from_rng_non_zero!(NonZeroI32, i32);
+value_from_rng_non_zero!(NonZeroI64, i64);
+value_from_rng_non_zero!(NonZeroI128, i128);
+value_from_rng_non_zero!(NonZeroIsize, isize);
+
 pub trait RangeFromRng {
     fn range_from_rng<T: Rng>(
         entropy_source: &mut T,
@@ -229,8 +709,15 @@ pub trait RangeFromRng {
         Self: Sized;
 }
 
-trait ZeroBasedRange {
+pub(crate) trait ZeroBasedRange: Sized {
     fn zero_based_range_from_rng(rng: &mut impl Rng, span: Self) -> Self;
+
+    // Calculates the value used to decide when to reject a random value for this span.
+    // Used when the same span is sampled many times, to avoid recalculating it.
+    fn rejection_threshold(span: Self) -> Self;
+
+    // Same as zero_based_range_from_rng, but with a precalculated rejection threshold.
+    fn zero_based_range_with_threshold(rng: &mut impl Rng, span: Self, threshold: Self) -> Self;
 }
 
 macro_rules! zero_based_range_from_rng_lemire {
@@ -243,25 +730,47 @@ macro_rules! zero_based_range_from_rng_lemire {
                 const SIZE_IN_BITS: usize = mem::size_of::<$output_type>() * 8;
                 let m =
                     <$bigger_type>::from(rng.random::<$output_type>()) * <$bigger_type>::from(span);
-                let mut high = (m >> SIZE_IN_BITS) as $output_type;
-                let mut low = m as $output_type;
+                let high = (m >> SIZE_IN_BITS) as $output_type;
+                let low = m as $output_type;
                 if low < span {
-                    let threshold = span.wrapping_neg() % span;
-                    let mut iterations = 0_u32;
-                    while low < threshold {
-                        iterations = iterations.wrapping_add(1);
-                        debug_assert!(
-                            iterations < 128,
-                            "Lemire rejection loop did not terminate: RNG may be broken"
-                        );
-                        let m = <$bigger_type>::from(rng.random::<$output_type>())
-                            * <$bigger_type>::from(span);
-                        high = (m >> SIZE_IN_BITS) as $output_type;
-                        low = m as $output_type;
+                    // The threshold is always less than span, so the rejection loop
+                    // can only be entered if low < span.
+                    // Checking that first avoids calculating the threshold in most cases.
+                    let threshold = Self::rejection_threshold(span);
+                    if low < threshold {
+                        return Self::zero_based_range_with_threshold(rng, span, threshold);
                     }
                 }
                 high
             }
+
+            #[inline]
+            fn rejection_threshold(span: Self) -> Self {
+                span.wrapping_neg() % span
+            }
+
+            #[inline]
+            #[allow(clippy::cast_possible_truncation)]
+            fn zero_based_range_with_threshold(
+                rng: &mut impl Rng,
+                span: Self,
+                threshold: Self,
+            ) -> Self {
+                const SIZE_IN_BITS: usize = mem::size_of::<$output_type>() * 8;
+                let mut iterations = 0_u32;
+                loop {
+                    let m = <$bigger_type>::from(rng.random::<$output_type>())
+                        * <$bigger_type>::from(span);
+                    if (m as $output_type) >= threshold {
+                        return (m >> SIZE_IN_BITS) as $output_type;
+                    }
+                    iterations = iterations.wrapping_add(1);
+                    debug_assert!(
+                        iterations < 128,
+                        "Lemire rejection loop did not terminate: RNG may be broken"
+                    );
+                }
+            }
         }
     };
 }
@@ -275,10 +784,24 @@ macro_rules! zero_based_range_from_rng {
         impl ZeroBasedRange for $output_type {
             #[inline]
             fn zero_based_range_from_rng(rng: &mut impl Rng, span: Self) -> Self {
-                let mut random_value: Self = rng.random();
+                Self::zero_based_range_with_threshold(rng, span, Self::rejection_threshold(span))
+            }
+
+            #[inline]
+            fn rejection_threshold(span: Self) -> Self {
+                // This is the largest random value that can be used without bias
                 let reduced_max = Self::MAX - span + 1;
-                let max_valid_value = Self::MAX - (reduced_max % span);
-                while random_value > max_valid_value {
+                Self::MAX - (reduced_max % span)
+            }
+
+            #[inline]
+            fn zero_based_range_with_threshold(
+                rng: &mut impl Rng,
+                span: Self,
+                threshold: Self,
+            ) -> Self {
+                let mut random_value: Self = rng.random();
+                while random_value > threshold {
                     random_value = rng.random();
                 }
                 random_value % span
@@ -295,37 +818,15 @@ macro_rules! zero_based_range_from_rng {
 zero_based_range_from_rng!(u128);
 
 macro_rules! range_from_rng {
-    ($output_type: ty, $unsigned_type: ty, $generate_type: ty) => {
+    ($output_type: ty, $generate_type: ty) => {
         impl RangeFromRng for $output_type {
-            #[allow(
-                clippy::cast_possible_truncation,
-                clippy::cast_sign_loss,
-                clippy::cast_possible_wrap,
-                clippy::cast_lossless
-            )]
+            #[inline]
             fn range_from_rng<T: Rng>(
                 rng: &mut T,
                 range: impl Into<GenerateRange<$output_type>>,
             ) -> Self {
-                const _: () = {
-                    assert!(mem::size_of::<$generate_type>() >= mem::size_of::<$output_type>());
-                };
-                let GenerateRange {
-                    start,
-                    end_inclusive,
-                } = range.into();
-                if start == <$output_type>::MIN && end_inclusive == <$output_type>::MAX {
-                    return rng.random::<$generate_type>() as $output_type;
-                }
-                assert!(start <= end_inclusive, "Inverted range");
-                let span = (end_inclusive.wrapping_sub(start).wrapping_add(1)) as $unsigned_type;
-                if span == 0 {
-                    return start;
-                }
-                start.wrapping_add(
-                    (<$generate_type>::zero_based_range_from_rng(rng, span as $generate_type)
-                        as $output_type),
-                )
+                IntSampler::<$output_type, $generate_type>::without_threshold(range.into())
+                    .sample_single(rng)
             }
         }
     };
@@ -335,115 +836,197 @@ macro_rules! range_from_rng {
 // which would probably perform marginally better.
 // However, using u16 makes it much easier to test that
 // the Lemire algorithm is correct and the distribution uniform.
-range_from_rng! {u8, u8, u16}
-range_from_rng! {i8, u8, u16}
+range_from_rng! {u8, u16}
+range_from_rng! {i8, u16}
 
-range_from_rng! {u16, u16, u32}
-range_from_rng! {i16, u16, u32}
+range_from_rng! {u16, u32}
+range_from_rng! {i16, u32}
 
-range_from_rng! {u32, u32, u32}
-range_from_rng! {i32, u32, u32}
+range_from_rng! {u32, u32}
+range_from_rng! {i32, u32}
 
-range_from_rng! {u64, u64, u64}
-range_from_rng! {i64, u64, u64}
+range_from_rng! {u64, u64}
+range_from_rng! {i64, u64}
 
-range_from_rng! {u128, u128, u128}
-range_from_rng! {i128, u128, u128}
+range_from_rng! {u128, u128}
+range_from_rng! {i128, u128}
 
 #[cfg(target_pointer_width = "16")]
-range_from_rng! {usize, usize, u32}
+range_from_rng! {usize, u32}
 #[cfg(target_pointer_width = "32")]
-range_from_rng! {usize, usize, u32}
+range_from_rng! {usize, u32}
 #[cfg(target_pointer_width = "64")]
-range_from_rng! {usize, usize, u64}
+range_from_rng! {usize, u64}
 
 #[cfg(target_pointer_width = "16")]
-range_from_rng! {isize, usize, u32}
+range_from_rng! {isize, u32}
 #[cfg(target_pointer_width = "32")]
-range_from_rng! {isize, usize, u32}
+range_from_rng! {isize, u32}
 #[cfg(target_pointer_width = "64")]
-range_from_rng! {isize, usize, u64}
+range_from_rng! {isize, u64}
 
 impl RangeFromRng for f32 {
-    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
     fn range_from_rng<T: Rng>(rng: &mut T, range: impl Into<GenerateRange<f32>>) -> Self {
+        let sampler = Self::new_sampler(range.into());
+        <Self as SampleUniform>::sample(&sampler, rng)
+    }
+}
+
+impl RangeFromRng for f64 {
+    fn range_from_rng<T: Rng>(rng: &mut T, range: impl Into<GenerateRange<f64>>) -> Self {
+        let sampler = Self::new_sampler(range.into());
+        <Self as SampleUniform>::sample(&sampler, rng)
+    }
+}
+
+impl RangeFromRng for char {
+    fn range_from_rng<T: Rng>(rng: &mut T, range: impl Into<GenerateRange<char>>) -> Self {
+        // The surrogates are skipped by drawing an index among the valid chars
         let GenerateRange {
             start,
             end_inclusive,
         } = range.into();
-        let span = end_inclusive - start;
-
-        // The simple algorithm is just to generate an integer of the same size and convert it
-        // to a float while scaling it.  However, this does not utilize the full dynamic range
-        // of the mantissa when the integer is small.  The rand crate seems to do this.
-        // An ideal algorithm should draw a real number, then round that to the nearest float
-        // representation, in order to allow all possible float values to be possible outcomes.
-        // This would be equivalent to drawing an int with virtually infinite size before
-        // converting to float.
-        // In practice, we just need enough bits to ensure that the mantissa is fully used.
-        // A u64 will suffice, unless it has enough leading zero bits that there are less
-        // than 24 remaining bits (because the mantissa has 23 bits plus an initial implicit 1).
-        // We thus check the number of leading 0 bits, and draw one more random u64
-        // to make the integer value u128 if necessary.
-        // It is theoretically possible that a u128 this still not enough, but the probability
-        // of that many leading zero bits is more than small enough to ignore.
-        // Always using u128 would be simpler, but not as fast.
-        let r = rng.random_u64();
-        let normalized = if (r >> 23) != 0 {
-            (r as f32) / 2_f32.powi(64)
-        } else {
-            // Make a random u128 by using 64 more random bits.
-            // Conversion via f64 may seem unnecessary, but going directly to f32
-            // is not possible without over/underflow problems.
-            // There are other ways around that, but this branch is not on the hot path
-            // so simplicity wins here.
-            let r = (u128::from(r) << 64) | u128::from(rng.random::<u64>());
-            ((r as f64) / 2_f64.powi(128)) as f32
-        };
-        normalized * span + start
+        index_to_char(rng.range(char_to_index(start)..=char_to_index(end_inclusive)))
     }
 }
 
-impl RangeFromRng for f64 {
-    #[allow(clippy::cast_precision_loss)]
-    fn range_from_rng<T: Rng>(rng: &mut T, range: impl Into<GenerateRange<f64>>) -> Self {
+impl RangeFromRng for Duration {
+    fn range_from_rng<T: Rng>(rng: &mut T, range: impl Into<GenerateRange<Duration>>) -> Self {
         let GenerateRange {
             start,
-            end_inclusive: end,
+            end_inclusive,
         } = range.into();
-        let span = end - start;
-
-        // The simple algorithm is just to generate an integer of the same size and convert it
-        // to a float while scaling it.  However, this does not utilize the full dynamic range
-        // of the mantissa when the integer is small.  The rand crate seems to do this.
-        // An ideal algorithm should draw a real number, then round that to the nearest float
-        // representation, in order to allow all possible float values to be possible outcomes.
-        // This would be equivalent to drawing an int with virtually infinite size before
-        // converting to float.
-        // In practice, we just need enough bits to ensure that the mantissa is fully used.
-        // A u64 will suffice, unless it has enough leading zero bits that there are less
-        // than 53 remaining bits (because the mantissa has 52 bits plus an initial implicit 1).
-        // We thus check the number of leading 0 bits, and draw one more random u64
-        // to make the integer value u128 if necessary.
-        // It is theoretically possible that a u128 this still not enough, but the probability
-        // of that many leading zero bits is more than small enough to ignore.
-        // Always using u128 would be simpler, but not as fast.
-        let r = rng.random_u64();
-        let normalized = if (r >> 52) != 0 {
-            (r as f64) / 2_f64.powi(64)
-        } else {
-            // Make a random u128 by using 64 more random bits.
-            let r = (u128::from(r) << 64) | u128::from(rng.random::<u64>());
-            (r as f64) / 2_f64.powi(128)
-        };
-        normalized * span + start
+        duration_from_nanos(rng.range(start.as_nanos()..=end_inclusive.as_nanos()))
+    }
+}
+
+// Macro to implement RangeFromRng for points in time, by adding a random duration to the start
+macro_rules! range_from_rng_time {
+    ($output_type: ty) => {
+        #[cfg(feature = "std")]
+        impl RangeFromRng for $output_type {
+            fn range_from_rng<T: Rng>(
+                rng: &mut T,
+                range: impl Into<GenerateRange<$output_type>>,
+            ) -> Self {
+                let GenerateRange {
+                    start,
+                    end_inclusive,
+                } = range.into();
+                let span = end_inclusive.duration_after(start).expect("Inverted range");
+                start + rng.range(Duration::ZERO..=span)
+            }
+        }
+    };
+}
+
+range_from_rng_time!(std::time::SystemTime);
+range_from_rng_time!(std::time::Instant);
+
+/// Generates a random f32 in [0, 1), using the full precision of the mantissa.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+#[inline]
+pub(crate) fn random_unit_f32(rng: &mut impl Rng) -> f32 {
+    // The simple algorithm is just to generate an integer of the same size and convert it
+    // to a float while scaling it.  However, this does not utilize the full dynamic range
+    // of the mantissa when the integer is small.  The rand crate seems to do this.
+    // An ideal algorithm should draw a real number, then round that down to the nearest float
+    // representation, in order to allow all possible float values to be possible outcomes.
+    // This would be equivalent to drawing an int with virtually infinite size before
+    // converting to float.
+    // In practice, we just need enough bits to ensure that the mantissa is fully used.
+    // A u64 will suffice, unless it has enough leading zero bits that there are less
+    // than 24 remaining bits (because the mantissa has 23 bits plus an initial implicit 1).
+    // We thus check the number of leading 0 bits, and draw one more random u64
+    // to make the integer value u128 if necessary.
+    // It is theoretically possible that a u128 this still not enough, but the probability
+    // of that many leading zero bits is more than small enough to ignore.
+    // Always using u128 would be simpler, but not as fast.
+    // The bits that do not fit in the mantissa are cleared before the conversion,
+    // as the conversion would otherwise round to nearest, which could round up to 1.
+    let r = rng.random_u64();
+    if (r >> 23) != 0 {
+        let r = r & (u64::MAX << (40 - r.leading_zeros()));
+        (r as f32) / 2_f32.powi(64)
+    } else {
+        // Make a random u128 by using 64 more random bits.
+        // Conversion via f64 may seem unnecessary, but going directly to f32
+        // is not possible without over/underflow problems.
+        // There are other ways around that, but this branch is not on the hot path
+        // so simplicity wins here.
+        let r = (u128::from(r) << 64) | u128::from(rng.random::<u64>());
+        let r = r & (u128::MAX << 104_u32.saturating_sub(r.leading_zeros()));
+        ((r as f64) / 2_f64.powi(128)) as f32
+    }
+}
+
+/// Generates a random f64 in [0, 1), using the full precision of the mantissa.
+#[allow(clippy::cast_precision_loss)]
+#[inline]
+pub(crate) fn random_unit_f64(rng: &mut impl Rng) -> f64 {
+    // The simple algorithm is just to generate an integer of the same size and convert it
+    // to a float while scaling it.  However, this does not utilize the full dynamic range
+    // of the mantissa when the integer is small.  The rand crate seems to do this.
+    // An ideal algorithm should draw a real number, then round that down to the nearest float
+    // representation, in order to allow all possible float values to be possible outcomes.
+    // This would be equivalent to drawing an int with virtually infinite size before
+    // converting to float.
+    // In practice, we just need enough bits to ensure that the mantissa is fully used.
+    // A u64 will suffice, unless it has enough leading zero bits that there are less
+    // than 53 remaining bits (because the mantissa has 52 bits plus an initial implicit 1).
+    // We thus check the number of leading 0 bits, and draw one more random u64
+    // to make the integer value u128 if necessary.
+    // It is theoretically possible that a u128 this still not enough, but the probability
+    // of that many leading zero bits is more than small enough to ignore.
+    // Always using u128 would be simpler, but not as fast.
+    // The bits that do not fit in the mantissa are cleared before the conversion,
+    // as the conversion would otherwise round to nearest, which could round up to 1.
+    let r = rng.random_u64();
+    if (r >> 52) != 0 {
+        let r = r & (u64::MAX << (11 - r.leading_zeros()));
+        (r as f64) / 2_f64.powi(64)
+    } else {
+        // Make a random u128 by using 64 more random bits.
+        let r = (u128::from(r) << 64) | u128::from(rng.random::<u64>());
+        let r = r & (u128::MAX << 75_u32.saturating_sub(r.leading_zeros()));
+        (r as f64) / 2_f64.powi(128)
+    }
+}
+
+/// Generates a random f64 in (0, 1).
+/// Intended for use with functions like `ln` that cannot handle 0.
+#[inline]
+pub(crate) fn random_nonzero_unit_f64(rng: &mut impl Rng) -> f64 {
+    loop {
+        let value = random_unit_f64(rng);
+        if value > 0.0 {
+            return value;
+        }
+    }
+}
+
+/// Generates the same u64 every time
+#[cfg(test)]
+pub(crate) struct ConstantRng(pub(crate) u64);
+
+#[cfg(test)]
+impl Rng for ConstantRng {
+    #[allow(clippy::cast_possible_truncation)]
+    fn random_u32(&mut self) -> u32 {
+        self.0 as u32
+    }
+
+    fn random_u64(&mut self) -> u64 {
+        self.0
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::rng::Rng;
+    use crate::rng::{ConstantRng, Rng};
     use crate::{SplitMix, Xoshiro256pp};
+    use core::num::{NonZeroI128, NonZeroU8, Wrapping};
+    use core::time::Duration;
 
     struct CountingRng(pub u64);
 
@@ -614,11 +1197,370 @@ mod tests {
 
         fn random_u64(&mut self) -> u64 {
             let random = self.0 >> 64;
-            self.0 = self.0 << 64;
+            self.0 <<= 64;
             random as u64
         }
     }
 
+    #[test]
+    fn test_random_float_is_below_1() {
+        let mut rng = ConstantRng(u64::MAX);
+        assert_eq!(rng.random::<f64>(), 1.0 - f64::EPSILON / 2.0);
+        assert_eq!(rng.random::<f32>(), 1.0 - f32::EPSILON / 2.0);
+        let mut rng = ConstantRng(0);
+        assert_eq!(rng.random::<f64>(), 0.0);
+        assert_eq!(rng.random::<f32>(), 0.0);
+    }
+
+    #[test]
+    fn test_random_float_is_uniform() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut count = [0; 10];
+        for _ in 0..10000 {
+            let value: f64 = rng.random();
+            assert!((0.0..1.0).contains(&value));
+            count[(value * 10.0) as usize] += 1;
+            let value: f32 = rng.random();
+            assert!((0.0..1.0).contains(&value));
+        }
+        assert!(count.iter().all(|c| (850..1150).contains(c)), "{count:?}");
+    }
+
+    #[test]
+    fn test_inclusive_float_range_reaches_end() {
+        let mut rng = ConstantRng(u64::MAX);
+        assert_eq!(rng.range(0.0..=1.0), 1.0);
+        assert_eq!(rng.range(0.0_f32..=1.0), 1.0);
+        assert_eq!(rng.range(0.0..1.0), 1.0 - f64::EPSILON / 2.0);
+        assert_eq!(rng.range(0.0_f32..1.0), 1.0 - f32::EPSILON / 2.0);
+        assert_eq!(rng.range(-3.5..=-3.5), -3.5);
+        assert_eq!(rng.range(0.1..=0.3), 0.3);
+        assert_eq!(rng.range(0.1_f32..=0.7), 0.7);
+        assert_eq!(rng.range::<f64>(..), f64::MAX);
+        assert_eq!(rng.range::<f32>(..), f32::MAX);
+        let mut rng = ConstantRng(0);
+        assert_eq!(rng.range::<f64>(..), f64::MIN);
+        assert_eq!(rng.range::<f32>(..), f32::MIN);
+    }
+
+    #[test]
+    fn test_inclusive_float_range_is_uniform() {
+        // A range with two floats should generate both with equal probability
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let above_one = 1.0 + f64::EPSILON;
+        let count = (0..10_000)
+            .filter(|_| rng.range(1.0..=above_one) == above_one)
+            .count();
+        assert!((4700..5300).contains(&count), "{count}");
+        let mut count = [0; 10];
+        for _ in 0..10_000 {
+            let value = rng.range(0.0..=10.0);
+            assert!((0.0..=10.0).contains(&value));
+            count[(value as usize).min(9)] += 1;
+        }
+        assert!(count.iter().all(|c| (850..1150).contains(c)), "{count:?}");
+    }
+
+    #[test]
+    fn test_full_float_range() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut negative = 0;
+        for _ in 0..1000 {
+            let value: f64 = rng.range(..);
+            assert!(value.is_finite());
+            if value < 0.0 {
+                negative += 1;
+            }
+            assert!(rng.range::<f32>(f32::MIN..=f32::MAX).is_finite());
+        }
+        assert!((400..600).contains(&negative), "{negative}");
+    }
+
+    #[test]
+    fn test_inclusive_float_range_end_is_not_much_more_likely() {
+        // The largest values from the unit interval are at most as far apart as the floats
+        // near the end of these ranges, so no more than two of them should give the end
+        macro_rules! assert_end_count {
+            ($range: expr, $shift: expr) => {
+                let range = $range;
+                let values: Vec<_> = (0..8)
+                    .map(|i| ConstantRng(u64::MAX - (i << $shift)).range(range.clone()))
+                    .collect();
+                assert_eq!(values[0], *range.end());
+                let ends = values.iter().filter(|&value| value == range.end()).count();
+                assert!(ends <= 2, "{values:?}");
+            };
+        }
+        assert_end_count!(0.0..=1.0, 11);
+        assert_end_count!(0.0..=3.0, 11);
+        assert_end_count!(0.0..=0.75, 11);
+        assert_end_count!(0.0_f32..=1.0, 40);
+        assert_end_count!(0.0_f32..=3.0, 40);
+        assert_end_count!(0.0_f32..=0.75, 40);
+    }
+
+    #[test]
+    fn test_wide_float_ranges() {
+        // Asserts that the values are in the range and about evenly split around its middle
+        macro_rules! assert_wide_range {
+            ($range: expr, $middle: expr) => {
+                let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+                let range = $range;
+                let mut below_middle = 0;
+                for _ in 0..1000 {
+                    let value = rng.range(range.clone());
+                    assert!(range.contains(&value), "{value}");
+                    if value < $middle {
+                        below_middle += 1;
+                    }
+                }
+                assert!((400..600).contains(&below_middle), "{below_middle}");
+            };
+        }
+        assert_wide_range!(0.0..=f64::MAX, f64::MAX / 2.0);
+        assert_wide_range!(0.0..f64::MAX, f64::MAX / 2.0);
+        assert_wide_range!(-f64::MAX..=0.0, -f64::MAX / 2.0);
+        assert_wide_range!(-f64::MAX..0.0, -f64::MAX / 2.0);
+        assert_wide_range!(-1.0_f32..=f32::MAX, f32::MAX / 2.0);
+        assert_wide_range!(0.0_f32..f32::MAX, f32::MAX / 2.0);
+        assert_wide_range!(-f32::MAX..=1.0, -f32::MAX / 2.0);
+        assert_wide_range!(-f32::MAX..0.0, -f32::MAX / 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Inverted range")]
+    fn test_empty_float_range_panics() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        #[allow(clippy::reversed_empty_ranges)]
+        let _ = rng.range(1.0..1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Inverted range")]
+    fn test_inverted_float_range_panics() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        #[allow(clippy::reversed_empty_ranges)]
+        let _ = rng.range(2.0_f32..=1.0);
+    }
+
+    #[test]
+    fn test_random_arrays() {
+        let mut rng1 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut rng2 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let key: [u8; 32] = rng1.random();
+        let mut expected = [0; 32];
+        rng2.fill_u8(&mut expected);
+        assert_eq!(key, expected);
+        let values: [u32; 3] = rng1.random();
+        assert_eq!(values, [rng2.random(), rng2.random(), rng2.random()]);
+        let empty: [u64; 0] = rng1.random();
+        assert_eq!(empty, []);
+    }
+
+    #[test]
+    fn test_random_tuples() {
+        let mut rng1 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut rng2 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let (a, b): (u32, bool) = rng1.random();
+        assert_eq!(a, rng2.random::<u32>());
+        assert_eq!(b, rng2.random::<bool>());
+        let long: (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u64) = rng1.random();
+        for _ in 0..11 {
+            rng2.random::<u8>();
+        }
+        assert_eq!(long.11, rng2.random::<u64>());
+    }
+
+    #[test]
+    fn test_random_wrappers() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let some = (0..10_000)
+            .filter(|_| rng.random::<Option<u32>>().is_some())
+            .count();
+        assert!((4700..5300).contains(&some), "{some}");
+        let mut rng1 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut rng2 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        assert_eq!(
+            rng1.random::<Wrapping<i16>>(),
+            Wrapping(rng2.random::<i16>())
+        );
+    }
+
+    #[test]
+    fn test_random_non_zero() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut count = [0; 256];
+        for _ in 0..25_500 {
+            count[usize::from(rng.random::<NonZeroU8>().get())] += 1;
+        }
+        assert_eq!(count[0], 0);
+        assert!(
+            count[1..].iter().all(|c| (40..170).contains(c)),
+            "{count:?}"
+        );
+        assert_ne!(rng.random::<NonZeroI128>().get(), 0);
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use crate::{Rng, SplitMix, ValueFromRng, Xoshiro256pp};
+
+        #[derive(ValueFromRng, Debug, PartialEq)]
+        struct Named<T> {
+            flag: bool,
+            #[rng(range = 10..20)]
+            ranged: u32,
+            value: T,
+        }
+
+        #[derive(ValueFromRng, Debug, PartialEq)]
+        struct Tuple(u8, #[rng(range = 0.0..=1.0)] f64);
+
+        #[derive(ValueFromRng, Debug, PartialEq)]
+        struct Unit;
+
+        #[derive(ValueFromRng, Debug, PartialEq)]
+        enum Weighted {
+            #[rng(weight = 3)]
+            Common,
+            Rare(#[rng(range = ..5)] i8),
+            #[allow(dead_code)]
+            #[rng(weight = 0)]
+            Never,
+        }
+
+        #[test]
+        fn derived_struct_matches_fields() {
+            let mut rng1 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+            let mut rng2 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+            let named: Named<u64> = rng1.random();
+            assert_eq!(
+                named,
+                Named {
+                    flag: rng2.random(),
+                    ranged: rng2.range(10..20),
+                    value: rng2.random(),
+                }
+            );
+            let tuple: Tuple = rng1.random();
+            assert_eq!(tuple, Tuple(rng2.random(), rng2.range(0.0..=1.0)));
+            assert_eq!(rng1.random::<Unit>(), Unit);
+        }
+
+        #[test]
+        fn derived_enum_is_weighted() {
+            let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+            let mut common = 0;
+            for _ in 0..10_000 {
+                match rng.random() {
+                    Weighted::Common => common += 1,
+                    Weighted::Rare(value) => assert!(value < 5),
+                    Weighted::Never => panic!("A variant with a weight of 0 was generated"),
+                }
+            }
+            assert!((7250..7750).contains(&common), "{common}");
+        }
+    }
+
+    #[test]
+    fn test_random_char() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut outside_bmp = 0;
+        for _ in 0..10_000 {
+            let value: char = rng.random();
+            if u32::from(value) > 0xFFFF {
+                outside_bmp += 1;
+            }
+        }
+        // 1_048_576 of the 1_112_064 valid chars are outside the Basic Multilingual Plane
+        assert!((9300..9550).contains(&outside_bmp), "{outside_bmp}");
+        let mut rng = ConstantRng(u64::MAX);
+        assert_eq!(rng.random::<char>(), char::MAX);
+    }
+
+    #[test]
+    fn test_char_range() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut count = [0; 26];
+        for _ in 0..26_000 {
+            count[(rng.range('a'..='z') as u8 - b'a') as usize] += 1;
+        }
+        assert!(count.iter().all(|c| (850..1150).contains(c)), "{count:?}");
+        // A range across the surrogates should only generate the chars on each side
+        let mut count = [0; 2];
+        for _ in 0..1000 {
+            match rng.range('\u{d7ff}'..='\u{e000}') {
+                '\u{d7ff}' => count[0] += 1,
+                '\u{e000}' => count[1] += 1,
+                value => panic!("{value:?}"),
+            }
+        }
+        assert!(count.iter().all(|c| (400..600).contains(c)), "{count:?}");
+    }
+
+    #[test]
+    #[should_panic(expected = "Inverted range")]
+    fn test_inverted_char_range_panics() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let _ = rng.range('z'..'a');
+    }
+
+    #[test]
+    fn test_duration_range() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut count = [0; 10];
+        for _ in 0..10_000 {
+            let value = rng.range(Duration::from_nanos(999_999_995)..Duration::new(1, 5));
+            count[(value.as_nanos() - 999_999_995) as usize] += 1;
+        }
+        assert!(count.iter().all(|c| (850..1150).contains(c)), "{count:?}");
+        let mut millis = 0;
+        for _ in 0..1000 {
+            let value = rng.range(Duration::ZERO..=Duration::from_secs(10));
+            assert!(value <= Duration::from_secs(10));
+            millis += value.as_millis();
+        }
+        assert!((4_700_000..5_300_000).contains(&millis), "{millis}");
+        let mut rng = ConstantRng(0);
+        assert_eq!(rng.range(..=Duration::MAX), Duration::ZERO);
+        assert_eq!(rng.range(Duration::MAX..=Duration::MAX), Duration::MAX);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_range() {
+        use std::time::{Instant, SystemTime};
+
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let end = start + Duration::from_secs(3600);
+        for _ in 0..1000 {
+            let value = rng.range(start..end);
+            assert!(value >= start && value < end);
+        }
+        let start = Instant::now();
+        let end = start + Duration::from_millis(10);
+        let mut after_middle = 0;
+        for _ in 0..1000 {
+            let value = rng.range(start..=end);
+            assert!(value >= start && value <= end);
+            if value > start + Duration::from_millis(5) {
+                after_middle += 1;
+            }
+        }
+        assert!((400..600).contains(&after_middle), "{after_middle}");
+        assert_eq!(rng.range(start..=start), start);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "Inverted range")]
+    fn test_inverted_time_range_panics() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let now = std::time::SystemTime::now();
+        let _ = rng.range(now + Duration::from_secs(1)..=now);
+    }
+
     #[test]
     fn test_float_ranges_f64() {
         for leading_zeros in 0..64 {
@@ -671,6 +1613,103 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_choose() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let items = [0_usize, 1, 2, 3, 4];
+        let mut count = [0; 5];
+        for _ in 0..10000 {
+            count[*rng.choose(&items).unwrap()] += 1;
+        }
+        assert!(count.iter().all(|c| (1800..2200).contains(c)), "{count:?}");
+
+        let empty: [u8; 0] = [];
+        assert_eq!(rng.choose(&empty), None);
+    }
+
+    #[test]
+    fn test_choose_mut() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut items = [0_u32; 4];
+        for _ in 0..1000 {
+            *rng.choose_mut(&mut items).unwrap() += 1;
+        }
+        assert_eq!(items.iter().sum::<u32>(), 1000);
+        assert!(items.iter().all(|c| (200..300).contains(c)), "{items:?}");
+
+        let mut empty: [u8; 0] = [];
+        assert_eq!(rng.choose_mut(&mut empty), None);
+    }
+
+    #[test]
+    fn test_choose_iter() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut count = [0; 5];
+        let mut unknown_length_count = [0; 5];
+        for _ in 0..10000 {
+            count[rng.choose_iter(0_usize..5).unwrap()] += 1;
+            // Filtering hides the length, so that reservoir sampling is used
+            unknown_length_count[rng
+                .choose_iter((0_usize..10).filter(|i| i % 2 == 0))
+                .unwrap()
+                / 2] += 1;
+        }
+        assert!(count.iter().all(|c| (1800..2200).contains(c)), "{count:?}");
+        assert!(
+            unknown_length_count
+                .iter()
+                .all(|c| (1800..2200).contains(c)),
+            "{unknown_length_count:?}"
+        );
+
+        assert_eq!(rng.choose_iter(0..0), None);
+        assert_eq!(rng.choose_iter((0..10).filter(|i| *i > 10)), None);
+        assert_eq!(rng.choose_iter([42].iter().filter(|_| true)), Some(&42));
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut numbers: Vec<u32> = (0..20).collect();
+        let (shuffled, remainder) = rng.partial_shuffle(&mut numbers, 5);
+        assert_eq!(shuffled.len(), 5);
+        assert_eq!(remainder.len(), 15);
+        numbers.sort_unstable();
+        assert_eq!(numbers, (0..20).collect::<Vec<u32>>());
+
+        let (shuffled, remainder) = rng.partial_shuffle(&mut numbers, 25);
+        assert_eq!(shuffled.len(), 20);
+        assert!(remainder.is_empty());
+
+        let mut empty: [u8; 0] = [];
+        let (shuffled, remainder) = rng.partial_shuffle(&mut empty, 1);
+        assert!(shuffled.is_empty() && remainder.is_empty());
+    }
+
+    #[test]
+    fn test_partial_shuffle_of_all_is_shuffle() {
+        let mut rng1 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut rng2 = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut numbers1: Vec<u32> = (0..20).collect();
+        let mut numbers2 = numbers1.clone();
+        rng1.partial_shuffle(&mut numbers1, 20);
+        rng2.shuffle(&mut numbers2);
+        assert_eq!(numbers1, numbers2);
+        assert_eq!(rng1.random_u64(), rng2.random_u64());
+    }
+
+    #[test]
+    fn test_partial_shuffle_is_uniform() {
+        let mut rng = Xoshiro256pp::from_entropy(&mut SplitMix::new(42));
+        let mut count = [0; 10];
+        for _ in 0..10000 {
+            let mut numbers: Vec<usize> = (0..10).collect();
+            let (shuffled, _) = rng.partial_shuffle(&mut numbers, 2);
+            count[shuffled[1]] += 1;
+        }
+        assert!(count.iter().all(|c| (850..1150).contains(c)), "{count:?}");
+    }
+
     #[test]
     fn test_shuffle_empty_slice() {
         let mut rng = CountingRng::new();
@@ -0,0 +1,529 @@
+use crate::alphabet::Alphabet;
+use crate::rng::CryptoRng;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Generates random tokens, like API keys and session ids,
+/// from an [Alphabet] using a [CryptoRng].
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{Alphabet, StdRng, SplitMix, TokenGenerator};
+/// let mut rng = StdRng::from_entropy(&mut SplitMix::new(42));
+/// let generator = TokenGenerator::with_entropy_bits(Alphabet::BASE64URL, 128);
+/// assert_eq!(generator.len(), 22);
+/// let token = generator.generate(&mut rng);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenGenerator {
+    alphabet: Alphabet,
+    length: usize,
+}
+
+impl TokenGenerator {
+    /// Creates a generator of tokens with a given number of characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `alphabet`: The characters of the tokens
+    /// * `length`: The number of characters in each token
+    ///
+    /// returns: [TokenGenerator]
+    #[must_use]
+    pub fn new(alphabet: Alphabet, length: usize) -> Self {
+        Self { alphabet, length }
+    }
+
+    /// Creates a generator of the shortest tokens that have at least a given number of bits
+    /// of entropy.
+    ///
+    /// # Arguments
+    ///
+    /// * `alphabet`: The characters of the tokens
+    /// * `bits`: The minimum entropy of each token, in bits
+    ///
+    /// returns: [TokenGenerator]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is positive and the alphabet has only one character.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn with_entropy_bits(alphabet: Alphabet, bits: u32) -> Self {
+        let bits_per_char = bits_per_char(alphabet.chars().len());
+        assert!(
+            bits == 0 || bits_per_char > 0.0,
+            "Alphabet must have more than one character"
+        );
+        let mut length = 0;
+        while (length as f64) * bits_per_char < f64::from(bits) {
+            length += 1;
+        }
+        Self { alphabet, length }
+    }
+
+    /// Returns the number of characters in each token.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns true if the tokens have no characters.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the entropy of each token in bits, which is the base 2 logarithm
+    /// of the number of possible tokens.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn entropy_bits(&self) -> f64 {
+        (self.length as f64) * bits_per_char(self.alphabet.chars().len())
+    }
+
+    /// Generates a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use, which must be cryptographically secure
+    ///
+    /// returns: A random token
+    pub fn generate(&self, rng: &mut impl CryptoRng) -> String {
+        rng.string(self.length, &self.alphabet)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn bits_per_char(alphabet_length: usize) -> f64 {
+    (alphabet_length as f64).log2()
+}
+
+/// The classes of characters that passwords are made of.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CharClass {
+    /// The lower case ASCII letters.
+    Lowercase,
+    /// The upper case ASCII letters.
+    Uppercase,
+    /// The digits.
+    Digits,
+    /// The printable ASCII characters that are not letters, digits or space.
+    Symbols,
+}
+
+impl CharClass {
+    const ALL: [Self; 4] = [
+        Self::Lowercase,
+        Self::Uppercase,
+        Self::Digits,
+        Self::Symbols,
+    ];
+
+    fn chars(self) -> impl Iterator<Item = char> {
+        let ascii = match self {
+            Self::Lowercase => "abcdefghijklmnopqrstuvwxyz",
+            Self::Uppercase => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Self::Digits => "0123456789",
+            Self::Symbols => "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~",
+        };
+        ascii.chars()
+    }
+
+    fn of(c: char) -> Self {
+        if c.is_ascii_lowercase() {
+            Self::Lowercase
+        } else if c.is_ascii_uppercase() {
+            Self::Uppercase
+        } else if c.is_ascii_digit() {
+            Self::Digits
+        } else {
+            Self::Symbols
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The rules that generated passwords must follow.
+/// By default, passwords are made of all the [CharClass]es without any other constraints.
+///
+/// The constraints do not bias the passwords: Every password that follows the rules
+/// is equally likely to be generated by [PasswordGenerator].
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{CharClass, PasswordGenerator, PasswordPolicy, StdRng, SplitMix};
+/// let mut rng = StdRng::from_entropy(&mut SplitMix::new(42));
+/// let policy = PasswordPolicy::new(16)
+///     .min_count(CharClass::Digits, 2)
+///     .min_count(CharClass::Symbols, 1)
+///     .no_repeats();
+/// let generator = PasswordGenerator::new(policy).unwrap();
+/// assert!(generator.entropy_bits() > 100.0);
+/// let password = generator.generate(&mut rng);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PasswordPolicy {
+    length: usize,
+    // The minimum number of characters of each class, or None if the class is excluded
+    min_counts: [Option<usize>; 4],
+    no_repeats: bool,
+}
+
+impl PasswordPolicy {
+    /// Creates a policy for passwords with a given number of characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The number of characters in each password
+    ///
+    /// returns: [PasswordPolicy]
+    #[must_use]
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            min_counts: [Some(0); 4],
+            no_repeats: false,
+        }
+    }
+
+    /// Requires passwords to have at least a number of characters of a class.
+    /// This includes the class if it was excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `class`: The class of characters
+    /// * `count`: The minimum number of characters of the class
+    ///
+    /// returns: [PasswordPolicy]
+    #[must_use]
+    pub fn min_count(mut self, class: CharClass, count: usize) -> Self {
+        self.min_counts[class.index()] = Some(count);
+        self
+    }
+
+    /// Excludes a class of characters from the passwords.
+    ///
+    /// # Arguments
+    ///
+    /// * `class`: The class of characters
+    ///
+    /// returns: [PasswordPolicy]
+    #[must_use]
+    pub fn exclude(mut self, class: CharClass) -> Self {
+        self.min_counts[class.index()] = None;
+        self
+    }
+
+    /// Requires all the characters of passwords to be different.
+    ///
+    /// returns: [PasswordPolicy]
+    #[must_use]
+    pub fn no_repeats(mut self) -> Self {
+        self.no_repeats = true;
+        self
+    }
+
+    /// Calculates the natural logarithm of the number of passwords that follow the policy.
+    /// This is the number of ways to pick the number of characters of each class,
+    /// times the number of ways to pick and place those characters.
+    #[allow(clippy::cast_precision_loss)]
+    fn ln_password_count(&self) -> f64 {
+        let mut ln_factorial = vec![0.0; self.length.max(32) + 1];
+        for n in 1..ln_factorial.len() {
+            ln_factorial[n] = ln_factorial[n - 1] + (n as f64).ln();
+        }
+        // ln_counts[n] is the logarithm of the number of ways to use n characters of the
+        // classes processed so far, divided by n! (the terms of an exponential generating
+        // function), which makes the ways of the classes easy to combine
+        let mut ln_counts = vec![f64::NEG_INFINITY; self.length + 1];
+        ln_counts[0] = 0.0;
+        for class in CharClass::ALL {
+            let Some(min_count) = self.min_counts[class.index()] else {
+                continue;
+            };
+            let class_length = class.chars().count();
+            let mut next = vec![f64::NEG_INFINITY; self.length + 1];
+            for (used, ln_count) in ln_counts.iter().enumerate() {
+                for count in min_count..=self.length - used {
+                    let ln_class_count = if self.no_repeats {
+                        if count > class_length {
+                            break;
+                        }
+                        ln_factorial[class_length]
+                            - ln_factorial[count]
+                            - ln_factorial[class_length - count]
+                    } else {
+                        (count as f64) * (class_length as f64).ln() - ln_factorial[count]
+                    };
+                    next[used + count] = ln_add(next[used + count], ln_count + ln_class_count);
+                }
+            }
+            ln_counts = next;
+        }
+        ln_counts[self.length] + ln_factorial[self.length]
+    }
+}
+
+/// Adds two numbers given as logarithms, and returns the logarithm of the sum.
+fn ln_add(a: f64, b: f64) -> f64 {
+    let (larger, smaller) = if a > b { (a, b) } else { (b, a) };
+    if smaller.is_infinite() {
+        larger
+    } else {
+        larger + (smaller - larger).exp().ln_1p()
+    }
+}
+
+/// Generates random passwords that follow a [PasswordPolicy], using a [CryptoRng].
+///
+/// Passwords are drawn from all the allowed characters, and drawn again
+/// if they break the rules of the policy. This keeps all the passwords that follow the rules
+/// equally likely, but it means that policies that few passwords follow would be slow.
+/// [PasswordGenerator::new] therefore rejects policies that fewer than one in a thousand
+/// passwords follow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordGenerator {
+    policy: PasswordPolicy,
+    chars: Vec<char>,
+    entropy_bits: f64,
+}
+
+impl PasswordGenerator {
+    /// Creates a new password generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy`: The rules that the passwords must follow
+    ///
+    /// returns: [PasswordGenerator], or an error if the policy cannot be used
+    ///
+    /// # Errors
+    ///
+    /// Returns [PasswordError::NoCharClasses] if all the classes have been excluded,
+    /// [PasswordError::Unsatisfiable] if no password can follow the policy, and
+    /// [PasswordError::TooRestrictive] if too few passwords follow the policy.
+    pub fn new(policy: PasswordPolicy) -> Result<Self, PasswordError> {
+        let chars: Vec<char> = CharClass::ALL
+            .into_iter()
+            .filter(|class| policy.min_counts[class.index()].is_some())
+            .flat_map(CharClass::chars)
+            .collect();
+        if chars.is_empty() {
+            return Err(PasswordError::NoCharClasses);
+        }
+        let ln_count = policy.ln_password_count();
+        if ln_count.is_infinite() {
+            return Err(PasswordError::Unsatisfiable);
+        }
+        // The number of passwords that are drawn before the rules are checked
+        let unconstrained = PasswordPolicy {
+            min_counts: policy.min_counts.map(|min_count| min_count.map(|_| 0)),
+            ..policy
+        };
+        let ln_candidates = unconstrained.ln_password_count();
+        if ln_count - ln_candidates < 0.001_f64.ln() {
+            return Err(PasswordError::TooRestrictive);
+        }
+        Ok(Self {
+            policy,
+            chars,
+            entropy_bits: ln_count / core::f64::consts::LN_2,
+        })
+    }
+
+    /// Returns the entropy of each password in bits, which is the base 2 logarithm
+    /// of the number of passwords that follow the policy.
+    #[must_use]
+    pub fn entropy_bits(&self) -> f64 {
+        self.entropy_bits
+    }
+
+    /// Generates a password.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use, which must be cryptographically secure
+    ///
+    /// returns: A random password
+    pub fn generate(&self, rng: &mut impl CryptoRng) -> String {
+        loop {
+            let password: String = if self.policy.no_repeats {
+                rng.choose_multiple(&self.chars, self.policy.length)
+                    .into_iter()
+                    .collect()
+            } else {
+                (0..self.policy.length)
+                    .map(|_| self.chars[rng.range(0..self.chars.len())])
+                    .collect()
+            };
+            if self.follows_policy(&password) {
+                return password;
+            }
+        }
+    }
+
+    fn follows_policy(&self, password: &str) -> bool {
+        let mut counts = [0; 4];
+        for c in password.chars() {
+            counts[CharClass::of(c).index()] += 1;
+        }
+        counts
+            .iter()
+            .zip(self.policy.min_counts)
+            .all(|(count, min_count)| *count >= min_count.unwrap_or(0))
+    }
+}
+
+/// The error returned when a [PasswordGenerator] is constructed with a policy that cannot be used.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PasswordError {
+    /// All the classes of characters were excluded.
+    NoCharClasses,
+    /// No password can follow the policy.
+    Unsatisfiable,
+    /// Fewer than one in a thousand passwords follow the policy.
+    TooRestrictive,
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoCharClasses => "password policy must include at least one class of characters",
+            Self::Unsatisfiable => "no password can follow the password policy",
+            Self::TooRestrictive => "too few passwords follow the password policy",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PasswordError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SplitMix, StdRng};
+
+    fn rng() -> StdRng {
+        StdRng::from_entropy(&mut SplitMix::new(42))
+    }
+
+    #[test]
+    fn token_length_from_entropy() {
+        let generator = TokenGenerator::with_entropy_bits(Alphabet::HEX, 128);
+        assert_eq!(generator.len(), 32);
+        assert!((generator.entropy_bits() - 128.0).abs() < 1e-9);
+        let generator = TokenGenerator::with_entropy_bits(Alphabet::ALPHANUMERIC, 128);
+        assert_eq!(generator.len(), 22);
+        assert!(generator.entropy_bits() >= 128.0);
+        let token = generator.generate(&mut rng());
+        assert_eq!(token.len(), 22);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn password_entropy() {
+        let generator = PasswordGenerator::new(PasswordPolicy::new(8)).unwrap();
+        assert!((generator.entropy_bits() - 8.0 * 94_f64.log2()).abs() < 1e-9);
+        // The passwords without digits are excluded
+        let generator =
+            PasswordGenerator::new(PasswordPolicy::new(8).min_count(CharClass::Digits, 1)).unwrap();
+        let expected = (94_f64.powi(8) - 84_f64.powi(8)).log2();
+        assert!((generator.entropy_bits() - expected).abs() < 1e-9);
+        // 10 * 9 * 8 passwords of distinct digits
+        let generator = PasswordGenerator::new(
+            PasswordPolicy::new(3)
+                .exclude(CharClass::Lowercase)
+                .exclude(CharClass::Uppercase)
+                .exclude(CharClass::Symbols)
+                .no_repeats(),
+        )
+        .unwrap();
+        assert!((generator.entropy_bits() - 720_f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn passwords_follow_policy() {
+        let mut rng = rng();
+        let generator = PasswordGenerator::new(
+            PasswordPolicy::new(12)
+                .min_count(CharClass::Digits, 3)
+                .min_count(CharClass::Symbols, 2)
+                .exclude(CharClass::Uppercase)
+                .no_repeats(),
+        )
+        .unwrap();
+        for _ in 0..1000 {
+            let password = generator.generate(&mut rng);
+            assert_eq!(password.chars().count(), 12);
+            assert!(password.chars().filter(char::is_ascii_digit).count() >= 3);
+            assert!(password.chars().filter(char::is_ascii_punctuation).count() >= 2);
+            assert!(!password.chars().any(|c| c.is_ascii_uppercase()));
+            let mut chars: Vec<char> = password.chars().collect();
+            chars.sort_unstable();
+            chars.dedup();
+            assert_eq!(chars.len(), 12);
+        }
+    }
+
+    #[test]
+    fn constraints_do_not_bias_passwords() {
+        // There are 36 * 36 - 26 * 26 = 620 passwords of two lower case letters or digits
+        // with at least one digit, and 100 of them have two digits
+        let mut rng = rng();
+        let generator = PasswordGenerator::new(
+            PasswordPolicy::new(2)
+                .min_count(CharClass::Digits, 1)
+                .exclude(CharClass::Uppercase)
+                .exclude(CharClass::Symbols),
+        )
+        .unwrap();
+        let two_digits = (0..62_000)
+            .filter(|_| {
+                generator
+                    .generate(&mut rng)
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+            })
+            .count();
+        assert!((9500..10500).contains(&two_digits), "{two_digits}");
+    }
+
+    #[test]
+    fn invalid_policies() {
+        let no_classes = CharClass::ALL
+            .into_iter()
+            .fold(PasswordPolicy::new(8), PasswordPolicy::exclude);
+        assert_eq!(
+            PasswordGenerator::new(no_classes),
+            Err(PasswordError::NoCharClasses)
+        );
+        assert_eq!(
+            PasswordGenerator::new(
+                PasswordPolicy::new(4)
+                    .min_count(CharClass::Digits, 3)
+                    .min_count(CharClass::Symbols, 2)
+            ),
+            Err(PasswordError::Unsatisfiable)
+        );
+        assert_eq!(
+            PasswordGenerator::new(
+                PasswordPolicy::new(11)
+                    .min_count(CharClass::Digits, 11)
+                    .no_repeats()
+            ),
+            Err(PasswordError::Unsatisfiable)
+        );
+        assert_eq!(
+            PasswordGenerator::new(PasswordPolicy::new(8).min_count(CharClass::Digits, 8)),
+            Err(PasswordError::TooRestrictive)
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::rng::{random_nonzero_unit_f64, Rng};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+/// Picks `amount` distinct indexes from `0..length`, in random order.
+/// See [Rng::sample_indices](crate::Rng::sample_indices()).
+pub(crate) fn sample_indices(rng: &mut impl Rng, length: usize, amount: usize) -> Vec<usize> {
+    assert!(
+        amount <= length,
+        "Cannot sample more indices than the length"
+    );
+    // Floyd's algorithm only needs memory for the picked indexes,
+    // but a partial shuffle is faster if a large part of the indexes are picked
+    if amount < length / 4 {
+        sample_floyd(rng, length, amount)
+    } else {
+        sample_partial_shuffle(rng, length, amount)
+    }
+}
+
+/// Floyd's algorithm (<https://doi.org/10.1145/30401.315746>),
+/// followed by a shuffle since the algorithm does not pick the indexes in random order.
+fn sample_floyd(rng: &mut impl Rng, length: usize, amount: usize) -> Vec<usize> {
+    let mut picked = BTreeSet::new();
+    let mut indices = Vec::with_capacity(amount);
+    for upper in length - amount..length {
+        // If the candidate has been picked already, the upper bound is picked instead.
+        // It cannot have been picked, as it was outside the range in earlier iterations.
+        let candidate = rng.range(0..=upper);
+        let index = if picked.insert(candidate) {
+            candidate
+        } else {
+            picked.insert(upper);
+            upper
+        };
+        indices.push(index);
+    }
+    rng.shuffle(&mut indices);
+    indices
+}
+
+/// The first `amount` steps of the Fisher-Yates shuffle of all the indexes.
+fn sample_partial_shuffle(rng: &mut impl Rng, length: usize, amount: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..length).collect();
+    for inx in 0..amount {
+        indices.swap(inx, rng.range(inx..length));
+    }
+    indices.truncate(amount);
+    indices
+}
+
+/// Picks `amount` distinct elements from an iterator, in random order,
+/// using reservoir sampling. See [Rng::choose_multiple_iter](crate::Rng::choose_multiple_iter()).
+pub(crate) fn choose_multiple_iter<I: IntoIterator>(
+    rng: &mut impl Rng,
+    items: I,
+    amount: usize,
+) -> Vec<I::Item> {
+    let mut items = items.into_iter();
+    let mut reservoir: Vec<I::Item> = items.by_ref().take(amount).collect();
+    if reservoir.len() == amount && amount > 0 {
+        // This is Algorithm L of Li (https://doi.org/10.1145/198429.198435), which
+        // calculates how many elements to skip before the next one enters the reservoir,
+        // rather than drawing a random number for every element.
+        #[allow(clippy::cast_precision_loss)]
+        let inverse_amount = 1.0 / amount as f64;
+        let mut w = (random_nonzero_unit_f64(rng).ln() * inverse_amount).exp();
+        loop {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let skip = (random_nonzero_unit_f64(rng).ln() / (-w).ln_1p()).floor() as usize;
+            match items.nth(skip) {
+                Some(item) => reservoir[rng.range(0..amount)] = item,
+                None => break,
+            }
+            w *= (random_nonzero_unit_f64(rng).ln() * inverse_amount).exp();
+        }
+    }
+    // The reservoir keeps the order of the elements that have not been replaced
+    rng.shuffle(&mut reservoir);
+    reservoir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    /// Picks two of five elements many times, and asserts that
+    /// all 20 ordered pairs are equally likely
+    fn assert_pairs_are_uniform(mut pick: impl FnMut(&mut Xoshiro256pp) -> Vec<usize>) {
+        let mut rng = rng();
+        let mut count = [0_usize; 25];
+        for _ in 0..20_000 {
+            let picked = pick(&mut rng);
+            assert_eq!(picked.len(), 2);
+            assert_ne!(picked[0], picked[1]);
+            count[picked[0] * 5 + picked[1]] += 1;
+        }
+        let observed: Vec<usize> = (0..25)
+            .filter(|inx| inx / 5 != inx % 5)
+            .map(|inx| count[inx])
+            .collect();
+        let chi_square = chi_square(&observed, &[1000.0; 20]);
+        // The critical value for 19 degrees of freedom at p = 0.001 is 43.82
+        assert!(chi_square < 43.82, "{chi_square}");
+    }
+
+    #[test]
+    fn floyd_is_uniform() {
+        assert_pairs_are_uniform(|rng| sample_floyd(rng, 5, 2));
+    }
+
+    #[test]
+    fn partial_shuffle_is_uniform() {
+        assert_pairs_are_uniform(|rng| sample_partial_shuffle(rng, 5, 2));
+    }
+
+    #[test]
+    fn reservoir_is_uniform() {
+        // Filtering hides the length of the iterator
+        assert_pairs_are_uniform(|rng| choose_multiple_iter(rng, (0..5).filter(|_| true), 2));
+    }
+
+    #[test]
+    fn reservoir_with_long_iterator() {
+        // Every element should be picked with probability 10 / 1000
+        let mut rng = rng();
+        let mut count = [0_usize; 10];
+        for _ in 0..10_000 {
+            for picked in choose_multiple_iter(&mut rng, 0..1000_usize, 10) {
+                count[picked / 100] += 1;
+            }
+        }
+        let chi_square = chi_square(&count, &[10_000.0; 10]);
+        // The critical value for 9 degrees of freedom at p = 0.001 is 27.88
+        assert!(chi_square < 27.88, "{chi_square}");
+    }
+
+    #[test]
+    fn sample_indices_are_distinct() {
+        let mut rng = rng();
+        for (length, amount) in [
+            (1000, 10),
+            (1000, 999),
+            (100, 25),
+            (10, 10),
+            (10, 0),
+            (0, 0),
+        ] {
+            let mut indices = sample_indices(&mut rng, length, amount);
+            assert_eq!(indices.len(), amount);
+            indices.sort_unstable();
+            indices.dedup();
+            assert_eq!(indices.len(), amount);
+            assert!(indices.iter().all(|inx| *inx < length));
+        }
+    }
+
+    #[test]
+    fn reservoir_with_short_iterator() {
+        let mut rng = rng();
+        let mut picked = choose_multiple_iter(&mut rng, 0..3, 5);
+        picked.sort_unstable();
+        assert_eq!(picked, [0, 1, 2]);
+        assert!(choose_multiple_iter(&mut rng, 0..3, 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot sample more indices than the length")]
+    fn too_many_indices_panics() {
+        let _ = sample_indices(&mut rng(), 5, 6);
+    }
+}
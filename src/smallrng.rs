@@ -1,11 +1,17 @@
+use crate::alphabet::Alphabet;
+use crate::distributions::Distribution;
 use crate::entropy::EntropySource;
+use crate::permutation::Permutation;
 use crate::ranges::GenerateRange;
 use crate::rng::Rng;
 use crate::rng::{RangeFromRng, ValueFromRng};
+use crate::weighted::{Weight, WeightError};
 use crate::xoshiro::Xoshiro256pp;
 #[cfg(feature = "std")]
 use crate::DefaultEntropy;
 use crate::SplitMix;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 /// This is a numerically good PRNG if you need something small and fast
 /// but not cryptographically secure.
@@ -97,17 +103,23 @@ impl SmallRng {
     pub fn random<T>(&mut self) -> T
     where
         T: ValueFromRng,
-        Self: Sized,
     {
         self.0.random()
     }
 
-    /// Generates a single random integer in a specified range.
+    /// Generates a single random integer, float, char, duration or point in time
+    /// in a specified range.
     /// The distribution is strictly uniform.
     /// The following types are supported:
-    /// u8, u16, u64, u128, usize, i8, i16, i64, i128, isize, f32, f64
+    /// u8, u16, u64, u128, usize, i8, i16, i64, i128, isize, f32, f64, char, `Duration`,
+    /// and `SystemTime` and `Instant` when `std` is enabled.
     ///
-    /// Any kind of range is supported for integers, but only `Range` for floats.
+    /// Any kind of range is supported, except unbounded ranges of points in time.
+    /// Unbounded float ranges extend to the smallest or largest finite value of the type,
+    /// and the end of an inclusive float range is a possible outcome.
+    /// Because of rounding, the end can be up to about twice as likely
+    /// as the other values of the range.
+    /// Durations and points in time have a resolution of one nanosecond.
     ///
     /// # Arguments
     ///
@@ -115,6 +127,11 @@ impl SmallRng {
     ///
     /// returns: A random value in the range
     ///
+    /// # Panics
+    ///
+    /// Panics if the range is inverted or empty, if a float bound is NaN or infinite,
+    /// or if a range of points in time is unbounded.
+    ///
     /// # Examples
     ///
     /// ```
@@ -129,11 +146,35 @@ impl SmallRng {
     pub fn range<T>(&mut self, range: impl Into<GenerateRange<T>>) -> T
     where
         T: RangeFromRng,
-        Self: Sized,
     {
         self.0.range(range)
     }
 
+    /// Draws a single value from a [Distribution].
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution`: The distribution to sample from
+    ///
+    /// returns: A value drawn from the distribution
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[cfg(feature = "std")]
+    /// {
+    /// let mut rng = smallrand::SmallRng::new();
+    /// let coin_flip = rng.sample(&smallrand::Bernoulli::new(0.5).unwrap());
+    /// }
+    /// ```
+    #[inline]
+    pub fn sample<T, D>(&mut self, distribution: &D) -> T
+    where
+        D: Distribution<T>,
+    {
+        self.0.sample(distribution)
+    }
+
     /// Provides an iterator that emits random values.
     ///
     /// returns: An iterator that outputs random values. Never None.
@@ -151,7 +192,6 @@ impl SmallRng {
     pub fn iter<'a, T>(&'a mut self) -> impl Iterator<Item = T> + 'a
     where
         T: ValueFromRng + 'a,
-        Self: Sized,
     {
         self.0.iter()
     }
@@ -176,7 +216,6 @@ impl SmallRng {
     pub fn fill<T>(&mut self, destination: &mut [T])
     where
         T: ValueFromRng,
-        Self: Sized,
     {
         self.0.fill(destination);
     }
@@ -199,13 +238,57 @@ impl SmallRng {
     /// }
     /// ```
     #[inline]
-    pub fn fill_u8(&mut self, destination: &mut [u8])
-    where
-        Self: Sized,
-    {
+    pub fn fill_u8(&mut self, destination: &mut [u8]) {
         self.0.fill_u8(destination);
     }
 
+    /// Generates a random string of characters from an [Alphabet].
+    /// Every character of the alphabet is equally likely at each position.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`: The number of characters to generate
+    /// * `alphabet`: The characters to pick from
+    ///
+    /// returns: A random string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[cfg(feature = "std")]
+    /// {
+    /// let mut rng = smallrand::SmallRng::new();
+    /// let token = rng.string(16, &smallrand::Alphabet::ALPHANUMERIC);
+    /// }
+    /// ```
+    #[inline]
+    pub fn string(&mut self, length: usize, alphabet: &Alphabet) -> String {
+        self.0.string(length, alphabet)
+    }
+
+    /// Fills a mutable slice of chars with random characters from an [Alphabet].
+    /// Every character of the alphabet is equally likely at each position.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination`: The slice to fill
+    /// * `alphabet`: The characters to pick from
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[cfg(feature = "std")]
+    /// {
+    /// let mut rng = smallrand::SmallRng::new();
+    /// let mut chars = ['\0'; 8];
+    /// rng.fill_chars(&mut chars, &smallrand::Alphabet::HEX);
+    /// }
+    /// ```
+    #[inline]
+    pub fn fill_chars(&mut self, destination: &mut [char], alphabet: &Alphabet) {
+        self.0.fill_chars(destination, alphabet);
+    }
+
     /// Shuffles the elements of a slice
     ///
     /// # Arguments
@@ -223,11 +306,92 @@ impl SmallRng {
     /// }
     /// ```
     #[inline]
-    pub fn shuffle<T>(&mut self, target: &mut [T])
+    pub fn shuffle<T>(&mut self, target: &mut [T]) {
+        self.0.shuffle(target);
+    }
+
+    /// Same as [Rng::partial_shuffle](crate::Rng::partial_shuffle()).
+    #[inline]
+    pub fn partial_shuffle<'a, T>(
+        &mut self,
+        target: &'a mut [T],
+        amount: usize,
+    ) -> (&'a mut [T], &'a mut [T]) {
+        self.0.partial_shuffle(target, amount)
+    }
+
+    /// Same as [Rng::permutation](crate::Rng::permutation()).
+    #[inline]
+    pub fn permutation(&mut self, length: usize) -> Permutation {
+        self.0.permutation(length)
+    }
+
+    /// Same as [Rng::choose](crate::Rng::choose()).
+    #[inline]
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        self.0.choose(items)
+    }
+
+    /// Same as [Rng::choose_mut](crate::Rng::choose_mut()).
+    #[inline]
+    pub fn choose_mut<'a, T>(&mut self, items: &'a mut [T]) -> Option<&'a mut T> {
+        self.0.choose_mut(items)
+    }
+
+    /// Same as [Rng::choose_iter](crate::Rng::choose_iter()).
+    #[inline]
+    pub fn choose_iter<I>(&mut self, items: I) -> Option<I::Item>
     where
-        Self: Sized,
+        I: IntoIterator,
     {
-        self.0.shuffle(target);
+        self.0.choose_iter(items)
+    }
+
+    /// Same as [Rng::sample_indices](crate::Rng::sample_indices()).
+    #[inline]
+    pub fn sample_indices(&mut self, length: usize, amount: usize) -> Vec<usize> {
+        self.0.sample_indices(length, amount)
+    }
+
+    /// Same as [Rng::choose_multiple](crate::Rng::choose_multiple()).
+    #[inline]
+    pub fn choose_multiple<'a, T>(&mut self, items: &'a [T], amount: usize) -> Vec<&'a T> {
+        self.0.choose_multiple(items, amount)
+    }
+
+    /// Same as [Rng::choose_multiple_iter](crate::Rng::choose_multiple_iter()).
+    #[inline]
+    pub fn choose_multiple_iter<I>(&mut self, items: I, amount: usize) -> Vec<I::Item>
+    where
+        I: IntoIterator,
+    {
+        self.0.choose_multiple_iter(items, amount)
+    }
+
+    /// Same as [Rng::choose_weighted](crate::Rng::choose_weighted()).
+    #[inline]
+    pub fn choose_weighted<'a, T, W>(
+        &mut self,
+        items: &'a [T],
+        weight: impl Fn(&T) -> W,
+    ) -> Result<&'a T, WeightError>
+    where
+        W: Weight,
+    {
+        self.0.choose_weighted(items, weight)
+    }
+
+    /// Same as [Rng::choose_multiple_weighted](crate::Rng::choose_multiple_weighted()).
+    #[inline]
+    pub fn choose_multiple_weighted<W>(
+        &mut self,
+        weights: &[W],
+        amount: usize,
+    ) -> Result<Vec<usize>, WeightError>
+    where
+        W: Weight,
+    {
+        self.0.choose_multiple_weighted(weights, amount)
     }
 }
 
@@ -240,7 +404,7 @@ impl Default for SmallRng {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Rng, SmallRng, SplitMix};
+    use crate::{Rng, SmallRng, SplitMix, Standard};
 
     #[test]
     fn test_forwarding() {
@@ -256,6 +420,11 @@ mod tests {
         let mut rng = SmallRng::from_entropy(&mut SplitMix::new(12345678));
         assert_ne!(rng.range::<u32>(0..42), rng.range::<u32>(0..42));
 
+        assert_ne!(
+            rng.sample::<u64, _>(&Standard),
+            rng.sample::<u64, _>(&Standard)
+        );
+
         {
             let mut i = rng.iter::<u128>();
             i.next();
@@ -276,6 +445,39 @@ mod tests {
         a2 = a1;
         rng.shuffle(&mut a2);
         assert_ne!(a1, a2);
+
+        assert_ne!(
+            rng.string(16, &crate::Alphabet::HEX),
+            rng.string(16, &crate::Alphabet::HEX)
+        );
+        let mut c1 = ['\0'; 16];
+        let mut c2 = ['\0'; 16];
+        rng.fill_chars(&mut c1, &crate::Alphabet::ALPHANUMERIC);
+        rng.fill_chars(&mut c2, &crate::Alphabet::ALPHANUMERIC);
+        assert_ne!(c1, c2);
+
+        a2 = a1;
+        rng.partial_shuffle(&mut a2, 10);
+        assert_ne!(a1, a2);
+        assert_ne!(
+            rng.permutation(100).collect::<Vec<_>>(),
+            rng.permutation(100).collect::<Vec<_>>()
+        );
+
+        assert!(rng.choose(&a1).is_some());
+        assert!(rng.choose_mut(&mut a1).is_some());
+        assert!(rng.choose_iter(a1.iter().filter(|_| true)).is_some());
+        assert!(rng.choose_weighted(&a1, |item| *item).is_ok());
+        assert_ne!(
+            rng.choose_multiple_weighted(&[1; 100], 10),
+            rng.choose_multiple_weighted(&[1; 100], 10)
+        );
+        assert_ne!(rng.sample_indices(100, 10), rng.sample_indices(100, 10));
+        assert_ne!(rng.choose_multiple(&a1, 10), rng.choose_multiple(&a1, 10));
+        assert_ne!(
+            rng.choose_multiple_iter(0..100, 10),
+            rng.choose_multiple_iter(0..100, 10)
+        );
     }
 
     #[test]
@@ -0,0 +1,347 @@
+use crate::distributions::Distribution;
+use crate::ranges::{char_to_index, duration_from_nanos, index_to_char, GenerateRange};
+use crate::rng::{random_unit_f32, random_unit_f64, Rng, ZeroBasedRange};
+use core::fmt::Debug;
+use core::mem;
+use core::time::Duration;
+
+/// A uniform distribution over a range of values.
+///
+/// This produces exactly the same values as [Rng::range](crate::Rng::range()),
+/// but does the calculations that only depend on the range once, when it is created.
+/// This makes it faster if many values are to be drawn from the same range.
+///
+/// The following types are supported:
+/// u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, char, `Duration`
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{SmallRng, Uniform};
+/// let mut rng = SmallRng::from_seed(42);
+/// let dice = Uniform::new(1..=6);
+/// let throws: Vec<u8> = (0..10).map(|_| rng.sample(&dice)).collect();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Uniform<T: SampleUniform>(T::Sampler);
+
+impl<T: SampleUniform> Uniform<T> {
+    /// Creates a new uniform distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `range`: The range of the uniform distribution.
+    ///   Any kind of range is supported. Unbounded float ranges
+    ///   extend to the smallest or largest finite value of the type.
+    ///
+    /// returns: [Uniform]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is inverted or empty, or if a float bound is NaN or infinite.
+    #[must_use]
+    pub fn new(range: impl Into<GenerateRange<T>>) -> Self {
+        Self(T::new_sampler(range.into()))
+    }
+}
+
+impl<T: SampleUniform> Distribution<T> for Uniform<T> {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> T {
+        T::sample(&self.0, rng)
+    }
+}
+
+/// This is the trait for types that [Uniform] supports.
+pub trait SampleUniform: Sized {
+    /// The precalculated state needed to sample a range.
+    type Sampler: Copy + Debug + PartialEq;
+
+    fn new_sampler(range: GenerateRange<Self>) -> Self::Sampler;
+
+    fn sample(sampler: &Self::Sampler, rng: &mut impl Rng) -> Self;
+}
+
+/// The precalculated state for integer ranges.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct IntSampler<T, G> {
+    start: T,
+    // A span of 0 means that the full range of the type is used
+    span: G,
+    threshold: G,
+}
+
+macro_rules! sample_uniform_int {
+    ($output_type: ty, $unsigned_type: ty, $generate_type: ty) => {
+        impl IntSampler<$output_type, $generate_type> {
+            /// Calculates the start and span of a range, but not the rejection threshold.
+            /// This is shared with [Rng::range], so that the same values are generated.
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_possible_wrap,
+                clippy::cast_lossless
+            )]
+            #[inline]
+            pub(crate) fn without_threshold(range: GenerateRange<$output_type>) -> Self {
+                const _: () = {
+                    assert!(mem::size_of::<$generate_type>() >= mem::size_of::<$output_type>());
+                };
+                let GenerateRange {
+                    start,
+                    end_inclusive,
+                } = range;
+                if start == <$output_type>::MIN && end_inclusive == <$output_type>::MAX {
+                    return IntSampler {
+                        start,
+                        span: 0,
+                        threshold: 0,
+                    };
+                }
+                assert!(start <= end_inclusive, "Inverted range");
+                IntSampler {
+                    start,
+                    span: (end_inclusive.wrapping_sub(start).wrapping_add(1)) as $unsigned_type
+                        as $generate_type,
+                    threshold: 0,
+                }
+            }
+
+            /// Draws a value without a precalculated rejection threshold, which is faster
+            /// if only one value is drawn.
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            #[inline]
+            pub(crate) fn sample_single(&self, rng: &mut impl Rng) -> $output_type {
+                if self.span == 0 {
+                    return rng.random::<$generate_type>() as $output_type;
+                }
+                self.start.wrapping_add(
+                    <$generate_type>::zero_based_range_from_rng(rng, self.span) as $output_type
+                )
+            }
+        }
+
+        impl SampleUniform for $output_type {
+            type Sampler = IntSampler<$output_type, $generate_type>;
+
+            fn new_sampler(range: GenerateRange<Self>) -> Self::Sampler {
+                let mut sampler = Self::Sampler::without_threshold(range);
+                if sampler.span != 0 {
+                    sampler.threshold = <$generate_type>::rejection_threshold(sampler.span);
+                }
+                sampler
+            }
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            #[inline]
+            fn sample(sampler: &Self::Sampler, rng: &mut impl Rng) -> Self {
+                if sampler.span == 0 {
+                    return rng.random::<$generate_type>() as $output_type;
+                }
+                sampler
+                    .start
+                    .wrapping_add(<$generate_type>::zero_based_range_with_threshold(
+                        rng,
+                        sampler.span,
+                        sampler.threshold,
+                    ) as $output_type)
+            }
+        }
+    };
+}
+
+sample_uniform_int! {u8, u8, u16}
+sample_uniform_int! {i8, u8, u16}
+
+sample_uniform_int! {u16, u16, u32}
+sample_uniform_int! {i16, u16, u32}
+
+sample_uniform_int! {u32, u32, u32}
+sample_uniform_int! {i32, u32, u32}
+
+sample_uniform_int! {u64, u64, u64}
+sample_uniform_int! {i64, u64, u64}
+
+sample_uniform_int! {u128, u128, u128}
+sample_uniform_int! {i128, u128, u128}
+
+#[cfg(target_pointer_width = "16")]
+sample_uniform_int! {usize, usize, u32}
+#[cfg(target_pointer_width = "32")]
+sample_uniform_int! {usize, usize, u32}
+#[cfg(target_pointer_width = "64")]
+sample_uniform_int! {usize, usize, u64}
+
+#[cfg(target_pointer_width = "16")]
+sample_uniform_int! {isize, usize, u32}
+#[cfg(target_pointer_width = "32")]
+sample_uniform_int! {isize, usize, u32}
+#[cfg(target_pointer_width = "64")]
+sample_uniform_int! {isize, usize, u64}
+
+// Chars are sampled as indexes among the valid chars, like in range_from_rng in rng.rs
+impl SampleUniform for char {
+    type Sampler = IntSampler<u32, u32>;
+
+    fn new_sampler(range: GenerateRange<Self>) -> Self::Sampler {
+        u32::new_sampler(GenerateRange {
+            start: char_to_index(range.start),
+            end_inclusive: char_to_index(range.end_inclusive),
+        })
+    }
+
+    #[inline]
+    fn sample(sampler: &Self::Sampler, rng: &mut impl Rng) -> Self {
+        index_to_char(u32::sample(sampler, rng))
+    }
+}
+
+impl SampleUniform for Duration {
+    type Sampler = IntSampler<u128, u128>;
+
+    fn new_sampler(range: GenerateRange<Self>) -> Self::Sampler {
+        u128::new_sampler(GenerateRange {
+            start: range.start.as_nanos(),
+            end_inclusive: range.end_inclusive.as_nanos(),
+        })
+    }
+
+    #[inline]
+    fn sample(sampler: &Self::Sampler, rng: &mut impl Rng) -> Self {
+        duration_from_nanos(u128::sample(sampler, rng))
+    }
+}
+
+/// The precalculated state for float ranges.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FloatSampler<T> {
+    offset: T,
+    scale: T,
+    multiplier: T,
+    end_inclusive: T,
+}
+
+macro_rules! sample_uniform_float {
+    ($output_type: ty, $random_unit: ident) => {
+        impl SampleUniform for $output_type {
+            type Sampler = FloatSampler<$output_type>;
+
+            #[inline]
+            fn new_sampler(range: GenerateRange<Self>) -> Self::Sampler {
+                let GenerateRange {
+                    start,
+                    end_inclusive,
+                } = range;
+                assert!(start <= end_inclusive, "Inverted range");
+                // The largest value from the unit interval is mapped to the end of the range,
+                // which makes the end reachable. Rounding can put it less than one ULP
+                // past the end, which is clamped when sampling. The end can still be up to
+                // about twice as likely as the other values, as rounding can map
+                // one more value from the unit interval to it.
+                let max_unit = 1.0 - <$output_type>::EPSILON / 2.0;
+                // If the scaled span does not fit in the type, the range is scaled down
+                // and the value is scaled back up after it has been drawn.
+                // A factor of 4 makes even the span of the full range of the type fit.
+                let (multiplier, inverse_multiplier) =
+                    if ((end_inclusive - start) / max_unit).is_finite() {
+                        (1.0, 1.0)
+                    } else {
+                        (4.0, 0.25)
+                    };
+                let offset = start * inverse_multiplier;
+                let scale = (end_inclusive * inverse_multiplier - offset) / max_unit;
+                FloatSampler {
+                    offset,
+                    scale,
+                    multiplier,
+                    end_inclusive,
+                }
+            }
+
+            #[inline]
+            fn sample(sampler: &Self::Sampler, rng: &mut impl Rng) -> Self {
+                (($random_unit(rng) * sampler.scale + sampler.offset) * sampler.multiplier)
+                    .min(sampler.end_inclusive)
+            }
+        }
+    };
+}
+
+sample_uniform_float!(f32, random_unit_f32);
+sample_uniform_float!(f64, random_unit_f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    macro_rules! same_as_range {
+        ($range: expr) => {
+            let mut rng1 = rng();
+            let mut rng2 = rng();
+            let uniform = Uniform::new($range);
+            for _ in 0..1000 {
+                assert_eq!(rng1.sample(&uniform), rng2.range($range));
+            }
+        };
+    }
+
+    #[test]
+    fn integers_are_the_same_as_range() {
+        same_as_range!(13_u8..42);
+        same_as_range!(-100_i8..=100);
+        same_as_range!(1000_u16..=50000);
+        same_as_range!(-1000_i16..);
+        same_as_range!(3_u32..u32::MAX / 3 * 2);
+        same_as_range!(i32::MIN..0);
+        same_as_range!(0_u64..(1 << 63) + 1);
+        same_as_range!(..=-7_i64);
+        same_as_range!(17_u128..u128::MAX / 3 * 2);
+        same_as_range!(i128::MIN..=i128::MAX);
+        same_as_range!(1_usize..=usize::MAX / 3 * 2);
+        same_as_range!(-5_isize..5);
+        same_as_range!(42_u32..=42);
+    }
+
+    #[test]
+    fn floats_are_the_same_as_range() {
+        same_as_range!(4.0_f32..42.0);
+        same_as_range!(-1e10_f64..1e-10);
+        same_as_range!(-1_f32..=1.0);
+        same_as_range!(..=f64::MAX);
+    }
+
+    #[test]
+    fn chars_are_the_same_as_range() {
+        same_as_range!('a'..='z');
+        same_as_range!('\u{d000}'..'\u{f000}');
+        same_as_range!(..=char::MAX);
+    }
+
+    #[test]
+    fn durations_are_the_same_as_range() {
+        same_as_range!(Duration::from_millis(10)..Duration::from_secs(3));
+        same_as_range!(..=Duration::MAX);
+    }
+
+    #[test]
+    fn full_range_is_the_same_as_random() {
+        let mut rng1 = rng();
+        let mut rng2 = rng();
+        let uniform = Uniform::<u64>::new(..);
+        for _ in 0..100 {
+            assert_eq!(rng1.sample(&uniform), rng2.random::<u64>());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Inverted range")]
+    fn inverted_range_panics() {
+        #[allow(clippy::reversed_empty_ranges)]
+        let _ = Uniform::new(10_u32..=5);
+    }
+}
@@ -0,0 +1,362 @@
+use crate::rng::Rng;
+use core::fmt;
+use core::str::FromStr;
+
+/// A universally unique identifier, as specified by RFC 9562.
+///
+/// Random UUIDs are generated with [Uuid::new_v4], and time-ordered ones with
+/// [Uuid::new_v7] or a [UuidV7Generator].
+/// They are formatted and parsed in the canonical hyphenated form,
+/// like `"f81d4fae-7dec-41d0-a765-00a0c91e6bf6"`.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{SmallRng, Uuid};
+/// let mut rng = SmallRng::from_seed(42);
+/// let id = Uuid::new_v4(&mut rng);
+/// let text = id.to_string();
+/// assert_eq!(text.parse::<Uuid>(), Ok(id));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+    /// The nil UUID, which has all bits set to zero.
+    pub const NIL: Self = Self([0; 16]);
+
+    /// Creates a UUID from its bytes, in big-endian order.
+    #[must_use]
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the bytes of the UUID, in big-endian order.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Returns the version of the UUID, which is 4 for random UUIDs and 7 for time-ordered ones.
+    #[must_use]
+    pub const fn version(&self) -> u8 {
+        self.0[6] >> 4
+    }
+
+    /// Generates a version 4 UUID, which has 122 random bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use
+    ///
+    /// returns: [Uuid]
+    pub fn new_v4(rng: &mut impl Rng) -> Self {
+        let mut bytes = [0; 16];
+        rng.fill_u8(&mut bytes);
+        Self::with_version(bytes, 4)
+    }
+
+    /// Generates a version 7 UUID, which starts with a timestamp followed by 74 random bits.
+    /// UUIDs generated in different milliseconds sort in the order they were generated,
+    /// but use a [UuidV7Generator] if UUIDs generated within the same millisecond
+    /// must sort in order too.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use
+    /// * `unix_time_ms`: The number of milliseconds since the Unix epoch.
+    ///   Only the lower 48 bits are used.
+    ///
+    /// returns: [Uuid]
+    pub fn new_v7(rng: &mut impl Rng, unix_time_ms: u64) -> Self {
+        Self::from_v7_parts(unix_time_ms, random_v7_bits(rng))
+    }
+
+    fn from_v7_parts(unix_time_ms: u64, random: u128) -> Self {
+        // The random bits are placed after the 48 bit timestamp,
+        // skipping the version and variant bits
+        let value =
+            (u128::from(unix_time_ms) << 80) | ((random >> 62) << 64) | (random & ((1 << 62) - 1));
+        Self::with_version(value.to_be_bytes(), 7)
+    }
+
+    fn with_version(mut bytes: [u8; 16], version: u8) -> Self {
+        bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+        // The variant of RFC 9562 UUIDs is the bits 10
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+}
+
+/// The number of random bits in a version 7 UUID.
+const V7_RANDOM_BITS: u32 = 74;
+
+fn random_v7_bits(rng: &mut impl Rng) -> u128 {
+    rng.random::<u128>() >> (128 - V7_RANDOM_BITS)
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (inx, byte) in self.0.iter().enumerate() {
+            if matches!(inx, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = UuidParseError;
+
+    /// Parses a UUID in the canonical hyphenated form. Both upper and lower case are accepted.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.as_bytes();
+        if text.len() != 36 {
+            return Err(UuidParseError::InvalidLength);
+        }
+        let mut bytes = [0; 16];
+        let mut digits = 0;
+        for (inx, c) in text.iter().enumerate() {
+            if matches!(inx, 8 | 13 | 18 | 23) {
+                if *c != b'-' {
+                    return Err(UuidParseError::InvalidCharacter);
+                }
+                continue;
+            }
+            let value = match c {
+                b'0'..=b'9' => c - b'0',
+                b'a'..=b'f' => c - b'a' + 10,
+                b'A'..=b'F' => c - b'A' + 10,
+                _ => return Err(UuidParseError::InvalidCharacter),
+            };
+            bytes[digits / 2] |= value << (if digits % 2 == 0 { 4 } else { 0 });
+            digits += 1;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// The error returned when a string cannot be parsed as a [Uuid].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UuidParseError {
+    /// The string did not have the 36 characters of the hyphenated form.
+    InvalidLength,
+    /// The string had a character that is not a hex digit, or a hyphen in the wrong place.
+    InvalidCharacter,
+}
+
+impl fmt::Display for UuidParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidLength => "UUID must have 36 characters",
+            Self::InvalidCharacter => "UUID must be hex digits separated by hyphens",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UuidParseError {}
+
+/// Generates version 7 UUIDs that sort in the order they were generated,
+/// even if they are generated within the same millisecond.
+///
+/// The random bits of each UUID are those of the previous one plus a random increment
+/// if the millisecond has not changed, which is "method 2" of RFC 9562.
+/// If the clock goes backwards, the timestamp of the previous UUID is used instead.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{SmallRng, UuidV7Generator};
+/// let mut rng = SmallRng::from_seed(42);
+/// let mut generator = UuidV7Generator::new();
+/// let first = generator.generate_at(&mut rng, 1_700_000_000_000);
+/// let second = generator.generate_at(&mut rng, 1_700_000_000_000);
+/// assert!(first < second);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UuidV7Generator {
+    // The timestamp and random bits of the previous UUID, if any
+    last: Option<(u64, u128)>,
+}
+
+impl UuidV7Generator {
+    /// Creates a new generator.
+    ///
+    /// returns: [UuidV7Generator]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a version 7 UUID with the current system time.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use
+    ///
+    /// returns: [Uuid]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system time is before the Unix epoch.
+    #[cfg(feature = "std")]
+    pub fn generate(&mut self, rng: &mut impl Rng) -> Uuid {
+        let unix_time = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .expect("System time is before the Unix epoch");
+        #[allow(clippy::cast_possible_truncation)]
+        self.generate_at(rng, unix_time.as_millis() as u64)
+    }
+
+    /// Generates a version 7 UUID with a given timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to use
+    /// * `unix_time_ms`: The number of milliseconds since the Unix epoch.
+    ///   Only the lower 48 bits are used.
+    ///
+    /// returns: [Uuid]
+    pub fn generate_at(&mut self, rng: &mut impl Rng, unix_time_ms: u64) -> Uuid {
+        let unix_time_ms = unix_time_ms & ((1 << 48) - 1);
+        let (unix_time_ms, random) = match self.last {
+            Some((last_unix_time_ms, last_random)) if unix_time_ms <= last_unix_time_ms => {
+                // The increment is random so that the next UUID cannot be guessed
+                let random = last_random + 1 + u128::from(rng.random_u32());
+                if random >> V7_RANDOM_BITS == 0 {
+                    (last_unix_time_ms, random)
+                } else {
+                    // The random bits have overflowed, so the timestamp is moved forward instead
+                    (last_unix_time_ms + 1, random_v7_bits(rng))
+                }
+            }
+            _ => (unix_time_ms, random_v7_bits(rng)),
+        };
+        self.last = Some((unix_time_ms, random));
+        Uuid::from_v7_parts(unix_time_ms, random)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    #[test]
+    fn format_and_parse() {
+        // The example from RFC 9562, appendix A.6
+        let text = "017f22e2-79b0-7cc3-98c4-dc0c0c07398f";
+        let uuid: Uuid = text.parse().unwrap();
+        assert_eq!(
+            uuid.as_bytes(),
+            &[
+                0x01, 0x7f, 0x22, 0xe2, 0x79, 0xb0, 0x7c, 0xc3, 0x98, 0xc4, 0xdc, 0x0c, 0x0c, 0x07,
+                0x39, 0x8f
+            ]
+        );
+        assert_eq!(uuid.version(), 7);
+        assert_eq!(uuid.to_string(), text);
+        assert_eq!(text.to_uppercase().parse(), Ok(uuid));
+        assert_eq!(
+            Uuid::NIL.to_string(),
+            "00000000-0000-0000-0000-000000000000"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "017f22e2-79b0-7cc3-98c4-dc0c0c07398".parse::<Uuid>(),
+            Err(UuidParseError::InvalidLength)
+        );
+        assert_eq!(
+            "017f22e2079b0-7cc3-98c4-dc0c0c07398f".parse::<Uuid>(),
+            Err(UuidParseError::InvalidCharacter)
+        );
+        assert_eq!(
+            "017f22e2-79b0-7cc3-98c4-dc0c0c07398g".parse::<Uuid>(),
+            Err(UuidParseError::InvalidCharacter)
+        );
+        assert_eq!(
+            "017f22e2-79b0-7cc3-98c4-dc0c0c0739ø".parse::<Uuid>(),
+            Err(UuidParseError::InvalidCharacter)
+        );
+    }
+
+    #[test]
+    fn v4_has_version_and_variant() {
+        let mut rng = rng();
+        let mut ones = [0_u32; 128];
+        for _ in 0..1000 {
+            let uuid = Uuid::new_v4(&mut rng);
+            assert_eq!(uuid.version(), 4);
+            assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+            let value = u128::from_be_bytes(*uuid.as_bytes());
+            for (bit, ones) in ones.iter_mut().enumerate() {
+                *ones += u32::from(value >> bit & 1 == 1);
+            }
+        }
+        // All the bits except the 6 fixed ones should be random
+        let random_bits = ones
+            .iter()
+            .filter(|ones| (400..600).contains(*ones))
+            .count();
+        assert_eq!(random_bits, 122);
+    }
+
+    #[test]
+    fn v7_has_timestamp() {
+        let mut rng = rng();
+        let uuid = Uuid::new_v7(&mut rng, 0x017f_22e2_79b0);
+        assert_eq!(uuid.version(), 7);
+        assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+        assert_eq!(uuid.as_bytes()[..6], [0x01, 0x7f, 0x22, 0xe2, 0x79, 0xb0]);
+        assert_ne!(Uuid::new_v7(&mut rng, 0x017f_22e2_79b0), uuid);
+    }
+
+    #[test]
+    fn v7_generator_is_monotonic() {
+        let mut rng = rng();
+        let mut generator = UuidV7Generator::new();
+        let mut previous = Uuid::NIL;
+        // The clock stands still and goes backwards
+        for unix_time_ms in [5, 5, 5, 6, 6, 4, 7] {
+            for _ in 0..1000 {
+                let uuid = generator.generate_at(&mut rng, unix_time_ms);
+                assert!(uuid > previous);
+                assert_eq!(uuid.version(), 7);
+                assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+                previous = uuid;
+            }
+        }
+    }
+
+    #[test]
+    fn v7_generator_overflow_moves_timestamp() {
+        let mut rng = rng();
+        let mut generator = UuidV7Generator {
+            last: Some((42, (1 << V7_RANDOM_BITS) - 1)),
+        };
+        let uuid = generator.generate_at(&mut rng, 42);
+        assert_eq!(uuid.as_bytes()[..6], [0, 0, 0, 0, 0, 43]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn v7_generator_with_system_time() {
+        let mut rng = rng();
+        let mut generator = UuidV7Generator::new();
+        let first = generator.generate(&mut rng);
+        let second = generator.generate(&mut rng);
+        assert!(first < second);
+        // The timestamp should be after 2020
+        assert!(first.as_bytes()[..6] > [0x01, 0x6f, 0x5e, 0x66, 0xe8, 0x00][..]);
+    }
+}
@@ -0,0 +1,1007 @@
+#![allow(clippy::module_name_repetitions)]
+
+use crate::distributions::Distribution;
+use crate::rng::{random_nonzero_unit_f64, random_unit_f64, Rng};
+use crate::weighted::private::{Sealed, WeightValue};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::fmt;
+
+/// A distribution that picks indexes with probabilities proportional to a set of weights.
+///
+/// This uses the alias method of Walker and Vose, which takes O(n) time to set up
+/// but only O(1) time per sample, regardless of how the weights are distributed.
+/// Integer weights are handled with integer arithmetic, so the probabilities are exact.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{SmallRng, WeightedIndex};
+/// let mut rng = SmallRng::from_seed(42);
+/// let items = ["common", "uncommon", "rare"];
+/// let rarity = WeightedIndex::new([70_u32, 25, 5]).unwrap();
+/// let item = items[rng.sample(&rarity)];
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedIndex {
+    table: AliasTable,
+    // The index to use if the column's threshold is not met
+    aliases: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum AliasTable {
+    // A column's index is used if a value in [0, total) is below its threshold
+    Integer { total: u64, thresholds: Vec<u64> },
+    // A column's index is used if a value in [0, 1) is below its threshold
+    Float { thresholds: Vec<f64> },
+}
+
+impl WeightedIndex {
+    /// Creates a new weighted index distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights`: The weights of the indexes, which can be integers up to 64 bits, f32 or f64.
+    ///   Weights of zero are allowed, and those indexes are never picked.
+    ///
+    /// returns: [WeightedIndex], or an error if the weights are invalid
+    ///
+    /// # Errors
+    ///
+    /// Returns [WeightError::NoItems] if there are no weights,
+    /// [WeightError::NegativeWeight] if a weight is negative,
+    /// [WeightError::NonFiniteWeight] if a weight is NaN or infinite,
+    /// [WeightError::AllWeightsZero] if all weights are zero,
+    /// and [WeightError::TotalWeightOverflow] if the sum of the weights
+    /// does not fit in a u64 (or an f64 for floats).
+    pub fn new<W: Weight>(weights: impl IntoIterator<Item = W>) -> Result<Self, WeightError> {
+        let weights = weights
+            .into_iter()
+            .map(Sealed::to_weight_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        match total_weight(weights.iter().copied().map(Ok))? {
+            WeightValue::Integer(total) => Ok(Self::from_integers(&weights, total)),
+            WeightValue::Float(total) => Ok(Self::from_floats(&weights, total)),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_integers(weights: &[WeightValue], total: u64) -> Self {
+        // Every column has a height of total, and every weight is multiplied by the
+        // number of columns so that the columns are filled exactly.
+        // This cannot overflow, as both factors fit in a u64.
+        let columns = weights.len() as u128;
+        let scaled = weights
+            .iter()
+            .map(|weight| u128::from(weight.to_u64()) * columns)
+            .collect();
+        let (thresholds, aliases) = build_alias_table(scaled, u128::from(total));
+        Self {
+            table: AliasTable::Integer {
+                total,
+                // The thresholds are at most the total, so they fit in a u64
+                thresholds: thresholds.into_iter().map(|t| t as u64).collect(),
+            },
+            aliases,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn from_floats(weights: &[WeightValue], total: f64) -> Self {
+        // Each weight is divided by the total first, as the inverse of a tiny total
+        // can overflow
+        let columns = weights.len() as f64;
+        let scaled = weights
+            .iter()
+            .map(|weight| weight.to_f64() / total * columns)
+            .collect();
+        let (thresholds, aliases) = build_alias_table(scaled, 1.0);
+        Self {
+            table: AliasTable::Float { thresholds },
+            aliases,
+        }
+    }
+}
+
+impl Distribution<usize> for WeightedIndex {
+    #[inline]
+    fn sample(&self, rng: &mut impl Rng) -> usize {
+        let column = rng.range(0..self.aliases.len());
+        let keep = match &self.table {
+            AliasTable::Integer { total, thresholds } => rng.range(0..*total) < thresholds[column],
+            AliasTable::Float { thresholds } => random_unit_f64(rng) < thresholds[column],
+        };
+        if keep {
+            column
+        } else {
+            self.aliases[column]
+        }
+    }
+}
+
+/// Builds an alias table using Vose's algorithm
+/// (<https://doi.org/10.1109/32.92917>).
+///
+/// Each weight is put in a column of the given height, which is then filled
+/// up with a part of a larger weight (the alias).
+/// The weights must be scaled so that they sum to the height times the number of weights.
+///
+/// returns: The thresholds and aliases of the columns
+fn build_alias_table<T>(mut scaled: Vec<T>, height: T) -> (Vec<T>, Vec<usize>)
+where
+    T: Copy + PartialOrd + core::ops::Sub<Output = T>,
+{
+    let mut thresholds = scaled.clone();
+    let mut aliases: Vec<usize> = (0..scaled.len()).collect();
+    let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+        (0..scaled.len()).partition(|inx| scaled[*inx] < height);
+    while let (Some(&small_inx), Some(&large_inx)) = (small.last(), large.last()) {
+        small.pop();
+        thresholds[small_inx] = scaled[small_inx];
+        aliases[small_inx] = large_inx;
+        // The large weight fills the rest of the small column
+        scaled[large_inx] = scaled[large_inx] - (height - scaled[small_inx]);
+        if scaled[large_inx] < height {
+            large.pop();
+            small.push(large_inx);
+        }
+    }
+    // The remaining columns are full. With floats, some of them may
+    // have been considered small because of rounding errors.
+    for inx in small.into_iter().chain(large) {
+        thresholds[inx] = height;
+        aliases[inx] = inx;
+    }
+    (thresholds, aliases)
+}
+
+/// Sums the weights, checking that they are valid.
+fn total_weight(
+    weights: impl Iterator<Item = Result<WeightValue, WeightError>>,
+) -> Result<WeightValue, WeightError> {
+    let mut total = None;
+    for weight in weights {
+        total = Some(match (total, weight?) {
+            (None, weight) => weight,
+            (Some(WeightValue::Integer(total)), WeightValue::Integer(weight)) => {
+                WeightValue::Integer(
+                    total
+                        .checked_add(weight)
+                        .ok_or(WeightError::TotalWeightOverflow)?,
+                )
+            }
+            (Some(WeightValue::Float(total)), WeightValue::Float(weight)) => {
+                WeightValue::Float(total + weight)
+            }
+            _ => unreachable!("All weights have the same type"),
+        });
+    }
+    match total {
+        None => Err(WeightError::NoItems),
+        Some(WeightValue::Integer(0)) => Err(WeightError::AllWeightsZero),
+        Some(WeightValue::Float(total)) if total.is_infinite() => {
+            Err(WeightError::TotalWeightOverflow)
+        }
+        Some(WeightValue::Float(total)) if total <= 0.0 => Err(WeightError::AllWeightsZero),
+        Some(total) => Ok(total),
+    }
+}
+
+/// Picks one of the items with a probability proportional to its weight,
+/// using a linear scan. See [Rng::choose_weighted](crate::Rng::choose_weighted()).
+pub(crate) fn choose_weighted<'a, T, W: Weight>(
+    rng: &mut impl Rng,
+    items: &'a [T],
+    weight: impl Fn(&T) -> W,
+) -> Result<&'a T, WeightError> {
+    // The weights are calculated twice rather than stored, to avoid allocating.
+    // They have all been checked by the time they are calculated the second time.
+    let weights = || items.iter().map(|item| weight(item).to_weight_value());
+    let index = match total_weight(weights())? {
+        WeightValue::Integer(total) => {
+            let mut value = rng.range(0..total);
+            weights().position(|weight| {
+                let weight = weight.map_or(0, WeightValue::to_u64);
+                let found = value < weight;
+                value = value.wrapping_sub(weight);
+                found
+            })
+        }
+        WeightValue::Float(total) => {
+            let mut value = random_unit_f64(rng) * total;
+            weights()
+                .position(|weight| {
+                    let weight = weight.map_or(0.0, WeightValue::to_f64);
+                    let found = value < weight;
+                    value -= weight;
+                    found
+                })
+                // Rounding errors can make the value larger than the sum of the weights
+                .or_else(|| {
+                    weights().rposition(|weight| weight.is_ok_and(|weight| weight.to_f64() > 0.0))
+                })
+        }
+    };
+    // The weights are not all zero, so an index is always found
+    Ok(&items[index.unwrap_or(items.len() - 1)])
+}
+
+/// Picks `amount` distinct indexes with probabilities proportional to their weights.
+/// See [Rng::choose_multiple_weighted](crate::Rng::choose_multiple_weighted()).
+pub(crate) fn choose_multiple_weighted<W: Weight>(
+    rng: &mut impl Rng,
+    weights: &[W],
+    amount: usize,
+) -> Result<Vec<usize>, WeightError> {
+    // This is the algorithm of Efraimidis and Spirakis (https://doi.org/10.1016/j.ipl.2005.11.003):
+    // Each index gets the key U^(1 / weight), and the indexes with the largest keys are picked.
+    // The logarithm of the key is used, to avoid underflow for small weights.
+    // A heap of the picked indexes is kept, with the smallest key on top.
+    let mut picked = BinaryHeap::with_capacity(amount.saturating_add(1));
+    let mut non_zero_weights = 0;
+    for (index, weight) in weights.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let weight = match weight.to_weight_value()? {
+            WeightValue::Integer(weight) => weight as f64,
+            WeightValue::Float(weight) => weight,
+        };
+        if weight == 0.0 || amount == 0 {
+            continue;
+        }
+        non_zero_weights += 1;
+        let key = Key {
+            ln_key: random_nonzero_unit_f64(rng).ln() / weight,
+            index,
+        };
+        if picked.len() < amount {
+            picked.push(Reverse(key));
+        } else if picked
+            .peek()
+            .is_some_and(|Reverse(smallest)| key > *smallest)
+        {
+            picked.pop();
+            picked.push(Reverse(key));
+        }
+    }
+    if non_zero_weights < amount {
+        return Err(WeightError::TooFewNonZeroWeights);
+    }
+    // The order of the keys is the order that the indexes would have been picked in
+    // if they were picked one at a time
+    Ok(picked
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(key)| key.index)
+        .collect())
+}
+
+/// The key of an index in choose_multiple_weighted.
+#[derive(Copy, Clone, Debug)]
+struct Key {
+    ln_key: f64,
+    index: usize,
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ln_key.total_cmp(&other.ln_key)
+    }
+}
+
+/// A weighted index sampler with integer weights that can be changed after it is created.
+///
+/// The weights are kept in a Fenwick tree (a binary indexed tree),
+/// so updating, adding and removing weights takes O(log n) time, as does sampling.
+/// The weights are integers and the sampling uses exact integer ranges,
+/// so the probabilities are exactly proportional to the weights.
+/// Use [WeightedIndex] instead if the weights never change, as it samples faster.
+///
+/// # Examples
+///
+/// ```
+/// use smallrand::{DynamicWeightedIndex, SmallRng};
+/// let mut rng = SmallRng::from_seed(42);
+/// let mut priorities = DynamicWeightedIndex::new([5, 1, 3]).unwrap();
+/// priorities.update(1, 10).unwrap();
+/// priorities.push(2).unwrap();
+/// let task = priorities.sample(&mut rng).unwrap();
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DynamicWeightedIndex {
+    weights: Vec<u64>,
+    // Element i (counting from 1) holds the sum of the weights in (i - lowest_bit(i), i],
+    // where the range is also counted from 1. Element 0 is not used.
+    tree: Vec<u64>,
+    total: u64,
+}
+
+impl DynamicWeightedIndex {
+    /// Creates a new dynamic weighted index sampler.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights`: The initial weights of the indexes. Weights of zero are allowed,
+    ///   and those indexes are not picked until their weights are changed.
+    ///
+    /// returns: [DynamicWeightedIndex], or an error if the weights are too large
+    ///
+    /// # Errors
+    ///
+    /// Returns [WeightError::TotalWeightOverflow] if the sum of the weights does not fit in a u64.
+    pub fn new(weights: impl IntoIterator<Item = u64>) -> Result<Self, WeightError> {
+        let mut result = Self::default();
+        for weight in weights {
+            result.push(weight)?;
+        }
+        Ok(result)
+    }
+
+    /// Returns the number of indexes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns true if there are no indexes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Returns the weight of an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[must_use]
+    pub fn weight(&self, index: usize) -> u64 {
+        self.weights[index]
+    }
+
+    /// Returns the sum of all the weights.
+    #[must_use]
+    pub fn total_weight(&self) -> u64 {
+        self.total
+    }
+
+    /// Changes the weight of an index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index to change the weight of
+    /// * `weight`: The new weight
+    ///
+    /// # Errors
+    ///
+    /// Returns [WeightError::TotalWeightOverflow] if the sum of the weights would not fit in a u64.
+    /// The weight is then left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn update(&mut self, index: usize, weight: u64) -> Result<(), WeightError> {
+        let old_weight = self.weights[index];
+        self.total = (self.total - old_weight)
+            .checked_add(weight)
+            .ok_or(WeightError::TotalWeightOverflow)?;
+        self.weights[index] = weight;
+        // No partial sum can be larger than the total, so these cannot overflow
+        let mut node = index + 1;
+        while node < self.tree.len() {
+            self.tree[node] = self.tree[node] - old_weight + weight;
+            node += lowest_bit(node);
+        }
+        Ok(())
+    }
+
+    /// Adds an index with a given weight. The new index is the current length.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight`: The weight of the new index
+    ///
+    /// # Errors
+    ///
+    /// Returns [WeightError::TotalWeightOverflow] if the sum of the weights would not fit in a u64.
+    /// The index is then not added.
+    pub fn push(&mut self, weight: u64) -> Result<(), WeightError> {
+        self.total = self
+            .total
+            .checked_add(weight)
+            .ok_or(WeightError::TotalWeightOverflow)?;
+        self.weights.push(weight);
+        // The new node covers its own weight and the nodes below it
+        // that are not covered by other nodes.
+        let node = self.weights.len();
+        let mut sum = weight;
+        let mut child = node - 1;
+        while child > node - lowest_bit(node) {
+            sum += self.tree[child];
+            child -= lowest_bit(child);
+        }
+        self.tree.push(sum);
+        Ok(())
+    }
+
+    /// Removes an index and returns its weight.
+    ///
+    /// Like [Vec::swap_remove], this is done by moving the last index into the place
+    /// of the removed one, so the last index gets the removed index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index to remove
+    ///
+    /// returns: The weight of the removed index
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> u64 {
+        let weight = self.weights[index];
+        let last = self.weights.len() - 1;
+        let last_weight = self.weights[last];
+        // The last weight is taken out of the total before it is moved,
+        // so neither of these can overflow
+        self.update(last, 0)
+            .expect("Removing a weight cannot overflow");
+        if index != last {
+            self.update(index, last_weight)
+                .expect("Moving a weight cannot overflow");
+        }
+        // The last node is not part of the sum of any other node, so it can just be removed
+        self.weights.pop();
+        self.tree.pop();
+        weight
+    }
+
+    /// Picks an index with a probability proportional to its weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng`: The random generator to pick the index with
+    ///
+    /// returns: The picked index, or None if the sum of the weights is zero
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<usize> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(self.find(rng.range(0..self.total)))
+    }
+
+    /// Finds the index where the cumulative sum of the weights passes a value,
+    /// by walking down the tree from the largest node.
+    fn find(&self, mut value: u64) -> usize {
+        let mut node = 0;
+        let mut step = (self.tree.len() - 1).next_power_of_two();
+        while step > 0 {
+            if node + step < self.tree.len() && self.tree[node + step] <= value {
+                node += step;
+                value -= self.tree[node];
+            }
+            step /= 2;
+        }
+        // The node is counted from 1, so the next index after it is equal to it
+        node
+    }
+}
+
+impl Default for DynamicWeightedIndex {
+    fn default() -> Self {
+        Self {
+            weights: Vec::new(),
+            tree: alloc::vec![0],
+            total: 0,
+        }
+    }
+}
+
+/// Returns the lowest set bit of a value.
+#[inline]
+fn lowest_bit(value: usize) -> usize {
+    value & value.wrapping_neg()
+}
+
+/// This is the trait for the types that can be used as weights,
+/// which are integers up to 64 bits, f32 and f64.
+/// It is sealed, so it cannot be implemented for other types.
+pub trait Weight: Copy + private::Sealed {}
+
+// The items of this module are public, but cannot be named outside the crate
+mod private {
+    use super::WeightError;
+
+    pub trait Sealed {
+        /// Converts the weight to the representation used internally, checking that it is valid.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the weight is negative, NaN or infinite.
+        fn to_weight_value(self) -> Result<WeightValue, WeightError>;
+    }
+
+    /// The representation of weights used internally.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum WeightValue {
+        Integer(u64),
+        Float(f64),
+    }
+
+    impl WeightValue {
+        pub(crate) fn to_u64(self) -> u64 {
+            match self {
+                Self::Integer(weight) => weight,
+                Self::Float(_) => unreachable!("All weights have the same type"),
+            }
+        }
+
+        pub(crate) fn to_f64(self) -> f64 {
+            match self {
+                Self::Float(weight) => weight,
+                Self::Integer(_) => unreachable!("All weights have the same type"),
+            }
+        }
+    }
+}
+
+macro_rules! unsigned_weight {
+    ($weight_type: ty) => {
+        impl Weight for $weight_type {}
+
+        impl private::Sealed for $weight_type {
+            #[allow(
+                clippy::cast_lossless,
+                clippy::cast_possible_truncation,
+                clippy::unnecessary_cast
+            )]
+            #[inline]
+            fn to_weight_value(self) -> Result<WeightValue, WeightError> {
+                Ok(WeightValue::Integer(self as u64))
+            }
+        }
+    };
+}
+
+macro_rules! signed_weight {
+    ($weight_type: ty) => {
+        impl Weight for $weight_type {}
+
+        impl private::Sealed for $weight_type {
+            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+            #[inline]
+            fn to_weight_value(self) -> Result<WeightValue, WeightError> {
+                if self < 0 {
+                    return Err(WeightError::NegativeWeight);
+                }
+                Ok(WeightValue::Integer(self as u64))
+            }
+        }
+    };
+}
+
+macro_rules! float_weight {
+    ($weight_type: ty) => {
+        impl Weight for $weight_type {}
+
+        impl private::Sealed for $weight_type {
+            #[inline]
+            fn to_weight_value(self) -> Result<WeightValue, WeightError> {
+                if !self.is_finite() {
+                    return Err(WeightError::NonFiniteWeight);
+                }
+                if self < 0.0 {
+                    return Err(WeightError::NegativeWeight);
+                }
+                Ok(WeightValue::Float(f64::from(self)))
+            }
+        }
+    };
+}
+
+unsigned_weight!(u8);
+unsigned_weight!(u16);
+unsigned_weight!(u32);
+unsigned_weight!(u64);
+unsigned_weight!(usize);
+signed_weight!(i8);
+signed_weight!(i16);
+signed_weight!(i32);
+signed_weight!(i64);
+signed_weight!(isize);
+float_weight!(f32);
+float_weight!(f64);
+
+/// The error returned when items are picked using invalid weights.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WeightError {
+    /// There were no items to pick from.
+    NoItems,
+    /// A weight was negative.
+    NegativeWeight,
+    /// A weight was NaN or infinite.
+    NonFiniteWeight,
+    /// All weights were zero, so there is nothing to pick.
+    AllWeightsZero,
+    /// The sum of the weights was too large to be represented.
+    TotalWeightOverflow,
+    /// There were fewer items with a non-zero weight than the number of items to pick.
+    TooFewNonZeroWeights,
+}
+
+impl fmt::Display for WeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoItems => "there are no items to pick from",
+            Self::NegativeWeight => "weights must not be negative",
+            Self::NonFiniteWeight => "weights must be finite",
+            Self::AllWeightsZero => "at least one weight must be positive",
+            Self::TotalWeightOverflow => "the sum of the weights is too large",
+            Self::TooFewNonZeroWeights => "too few items have a positive weight",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WeightError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::chi_square;
+    use crate::{SplitMix, Xoshiro256pp};
+
+    const SAMPLES: usize = 100_000;
+
+    fn rng() -> Xoshiro256pp {
+        Xoshiro256pp::from_entropy(&mut SplitMix::new(42))
+    }
+
+    fn assert_fits(mut pick: impl FnMut(&mut Xoshiro256pp) -> usize, weights: &[f64]) {
+        let mut rng = rng();
+        let mut observed = vec![0; weights.len()];
+        for _ in 0..SAMPLES {
+            observed[pick(&mut rng)] += 1;
+        }
+        let total: f64 = weights.iter().sum();
+        let expected: Vec<f64> = weights
+            .iter()
+            .map(|weight| weight / total * SAMPLES as f64)
+            .collect();
+        for (observed, expected) in observed.iter().zip(&expected) {
+            if *expected == 0.0 {
+                assert_eq!(*observed, 0);
+            }
+        }
+        let (observed, expected): (Vec<usize>, Vec<f64>) = observed
+            .into_iter()
+            .zip(expected)
+            .filter(|(_, expected)| *expected > 0.0)
+            .unzip();
+        let chi_square = chi_square(&observed, &expected);
+        // The critical value for 6 degrees of freedom at p = 0.001 is 22.46
+        assert!(chi_square < 22.46, "{chi_square}");
+    }
+
+    #[test]
+    fn integer_weights_fit_distribution() {
+        let weights = [10_u32, 0, 1, 35, 7, 7, 20, 2];
+        let distribution = WeightedIndex::new(weights).unwrap();
+        assert_fits(|rng| rng.sample(&distribution), &weights.map(f64::from));
+    }
+
+    #[test]
+    fn float_weights_fit_distribution() {
+        let weights = [0.1, 2.5, 0.0, 0.7, 1.2, 0.05, 3.0, 0.45];
+        let distribution = WeightedIndex::new(weights).unwrap();
+        assert_fits(|rng| rng.sample(&distribution), &weights);
+    }
+
+    #[test]
+    fn subnormal_float_weights_fit_distribution() {
+        let weights = [1e-310, 3e-310];
+        let distribution = WeightedIndex::new(weights).unwrap();
+        assert_fits(|rng| rng.sample(&distribution), &weights);
+    }
+
+    #[test]
+    fn integer_alias_table_is_exact() {
+        // Each index must get exactly its share of the total area of the table
+        let weights = [3_u64, 1, 4, 1, 5, 9, 2, 6];
+        let distribution = WeightedIndex::new(weights).unwrap();
+        let AliasTable::Integer { total, thresholds } = &distribution.table else {
+            panic!("Expected an integer table");
+        };
+        let mut area = [0_u64; 8];
+        for (column, threshold) in thresholds.iter().enumerate() {
+            area[column] += threshold;
+            area[distribution.aliases[column]] += total - threshold;
+        }
+        assert_eq!(area, weights.map(|weight| weight * 8));
+    }
+
+    #[test]
+    fn huge_integer_weights() {
+        let distribution = WeightedIndex::new([u64::MAX / 2, u64::MAX / 2, 1]).unwrap();
+        let mut rng = rng();
+        assert!(distribution
+            .sample_iter(&mut rng)
+            .take(1000)
+            .all(|inx| inx < 2));
+    }
+
+    #[test]
+    fn single_weight() {
+        let distribution = WeightedIndex::new([0.5_f32]).unwrap();
+        let mut rng = rng();
+        assert!(distribution
+            .sample_iter(&mut rng)
+            .take(100)
+            .all(|inx| inx == 0));
+    }
+
+    #[test]
+    fn choose_weighted_fits_distribution() {
+        let items = [
+            (0, 10_i64),
+            (1, 0),
+            (2, 1),
+            (3, 35),
+            (4, 7),
+            (5, 7),
+            (6, 20),
+            (7, 2),
+        ];
+        assert_fits(
+            |rng| rng.choose_weighted(&items, |item| item.1).unwrap().0,
+            &items.map(|item| item.1 as f64),
+        );
+        let items = [
+            (0, 0.1),
+            (1, 2.5),
+            (2, 0.0),
+            (3, 0.7),
+            (4, 1.2),
+            (5, 0.05),
+            (6, 3.0),
+            (7, 0.45),
+        ];
+        assert_fits(
+            |rng| rng.choose_weighted(&items, |item| item.1).unwrap().0,
+            &items.map(|item| item.1),
+        );
+    }
+
+    #[test]
+    fn dynamic_weights_fit_distribution() {
+        let mut distribution = DynamicWeightedIndex::new([10, 0, 1, 35, 7, 99]).unwrap();
+        distribution.update(5, 20).unwrap();
+        distribution.push(2).unwrap();
+        distribution.push(100).unwrap();
+        assert_eq!(distribution.remove(1), 0);
+        distribution.push(7).unwrap();
+        distribution.update(1, 0).unwrap();
+        assert_fits(
+            |rng| distribution.sample(rng).unwrap(),
+            &[10.0, 0.0, 1.0, 35.0, 7.0, 20.0, 2.0, 7.0],
+        );
+    }
+
+    #[test]
+    fn dynamic_tree_matches_weights() {
+        // Check every partial sum after a series of random changes
+        let mut rng = rng();
+        let mut distribution = DynamicWeightedIndex::default();
+        for _ in 0..1000 {
+            match rng.range(0..3) {
+                0 => distribution.push(rng.range(0..100)).unwrap(),
+                1 if !distribution.is_empty() => {
+                    distribution.remove(rng.range(0..distribution.len()));
+                }
+                _ if !distribution.is_empty() => distribution
+                    .update(rng.range(0..distribution.len()), rng.range(0..100))
+                    .unwrap(),
+                _ => {}
+            }
+            for node in 1..distribution.tree.len() {
+                let expected: u64 = distribution.weights[node - lowest_bit(node)..node]
+                    .iter()
+                    .sum();
+                assert_eq!(distribution.tree[node], expected);
+            }
+            assert_eq!(distribution.total, distribution.weights.iter().sum::<u64>());
+        }
+    }
+
+    #[test]
+    fn dynamic_find_covers_every_value() {
+        // Every value of the range must map to the index that covers it
+        let distribution = DynamicWeightedIndex::new([2, 0, 3, 1, 0, 0, 4]).unwrap();
+        let expected = [0, 0, 2, 2, 2, 3, 6, 6, 6, 6];
+        for (value, expected) in (0..).zip(expected) {
+            assert_eq!(distribution.find(value), expected);
+        }
+    }
+
+    #[test]
+    fn dynamic_without_weight() {
+        let mut rng = rng();
+        let mut distribution = DynamicWeightedIndex::default();
+        assert_eq!(distribution.sample(&mut rng), None);
+        distribution.push(0).unwrap();
+        assert_eq!(distribution.sample(&mut rng), None);
+        distribution.update(0, 3).unwrap();
+        assert_eq!(distribution.sample(&mut rng), Some(0));
+        assert_eq!(distribution.remove(0), 3);
+        assert!(distribution.is_empty());
+        assert_eq!(distribution.sample(&mut rng), None);
+    }
+
+    #[test]
+    fn dynamic_overflow() {
+        let mut distribution = DynamicWeightedIndex::new([u64::MAX - 1, 1]).unwrap();
+        assert_eq!(distribution.push(1), Err(WeightError::TotalWeightOverflow));
+        assert_eq!(
+            distribution.update(1, 2),
+            Err(WeightError::TotalWeightOverflow)
+        );
+        assert_eq!(distribution.len(), 2);
+        assert_eq!(distribution.weight(1), 1);
+        assert_eq!(distribution.total_weight(), u64::MAX);
+        assert_eq!(
+            DynamicWeightedIndex::new([u64::MAX, 1]),
+            Err(WeightError::TotalWeightOverflow)
+        );
+    }
+
+    #[test]
+    fn dynamic_remove_large_weights() {
+        let mut distribution = DynamicWeightedIndex::new([5, u64::MAX - 5]).unwrap();
+        assert_eq!(distribution.remove(0), 5);
+        assert_eq!(distribution.len(), 1);
+        assert_eq!(distribution.weight(0), u64::MAX - 5);
+        assert_eq!(distribution.total_weight(), u64::MAX - 5);
+        assert_eq!(distribution.tree[1], u64::MAX - 5);
+        assert_eq!(distribution.remove(0), u64::MAX - 5);
+        assert!(distribution.is_empty());
+        assert_eq!(distribution.total_weight(), 0);
+    }
+
+    #[test]
+    fn choose_multiple_weighted_fits_distribution() {
+        // The indexes must be picked in the same order as if they were picked one at a time
+        let weights = [1, 2, 3, 4];
+        let mut rng = rng();
+        let mut count = [0; 16];
+        for _ in 0..SAMPLES {
+            let picked = rng.choose_multiple_weighted(&weights, 2).unwrap();
+            count[picked[0] * 4 + picked[1]] += 1;
+        }
+        let mut observed = Vec::new();
+        let mut expected = Vec::new();
+        for first in 0..4 {
+            for second in (0..4).filter(|second| *second != first) {
+                observed.push(count[first * 4 + second]);
+                expected.push(
+                    weights[first] as f64 / 10.0 * weights[second] as f64
+                        / (10 - weights[first]) as f64
+                        * SAMPLES as f64,
+                );
+            }
+        }
+        let chi_square = chi_square(&observed, &expected);
+        // The critical value for 11 degrees of freedom at p = 0.001 is 31.26
+        assert!(chi_square < 31.26, "{chi_square}");
+    }
+
+    #[test]
+    fn choose_multiple_weighted_skips_zero_weights() {
+        let mut rng = rng();
+        for _ in 0..100 {
+            let mut picked = rng
+                .choose_multiple_weighted(&[0.0, 1e-300, 0.0, 5.0, 1e300], 3)
+                .unwrap();
+            picked.sort_unstable();
+            assert_eq!(picked, [1, 3, 4]);
+        }
+        assert!(rng
+            .choose_multiple_weighted(&[0_u8; 0], 0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn choose_multiple_weighted_errors() {
+        let mut rng = rng();
+        assert_eq!(
+            rng.choose_multiple_weighted(&[1, 0, 2], 3),
+            Err(WeightError::TooFewNonZeroWeights)
+        );
+        assert_eq!(
+            rng.choose_multiple_weighted(&[1_u64; 3], 4),
+            Err(WeightError::TooFewNonZeroWeights)
+        );
+        assert_eq!(
+            rng.choose_multiple_weighted(&[1, -1], 1),
+            Err(WeightError::NegativeWeight)
+        );
+        assert_eq!(
+            rng.choose_multiple_weighted(&[1.0, f64::NAN], 1),
+            Err(WeightError::NonFiniteWeight)
+        );
+    }
+
+    #[test]
+    fn invalid_weights() {
+        assert_eq!(
+            WeightedIndex::new(Vec::<u32>::new()),
+            Err(WeightError::NoItems)
+        );
+        assert_eq!(
+            WeightedIndex::new([1, -1, 2]),
+            Err(WeightError::NegativeWeight)
+        );
+        assert_eq!(
+            WeightedIndex::new([1.0, -0.5]),
+            Err(WeightError::NegativeWeight)
+        );
+        assert_eq!(
+            WeightedIndex::new([1.0, f64::NAN]),
+            Err(WeightError::NonFiniteWeight)
+        );
+        assert_eq!(
+            WeightedIndex::new([f32::INFINITY]),
+            Err(WeightError::NonFiniteWeight)
+        );
+        assert_eq!(
+            WeightedIndex::new([0_u8, 0]),
+            Err(WeightError::AllWeightsZero)
+        );
+        assert_eq!(
+            WeightedIndex::new([0.0, 0.0]),
+            Err(WeightError::AllWeightsZero)
+        );
+        assert_eq!(
+            WeightedIndex::new([u64::MAX, 1]),
+            Err(WeightError::TotalWeightOverflow)
+        );
+        assert_eq!(
+            WeightedIndex::new([f64::MAX, f64::MAX]),
+            Err(WeightError::TotalWeightOverflow)
+        );
+
+        let mut rng = rng();
+        let empty: [u32; 0] = [];
+        assert_eq!(
+            rng.choose_weighted(&empty, |weight| *weight),
+            Err(WeightError::NoItems)
+        );
+        assert_eq!(
+            rng.choose_weighted(&[1, -2], |weight| *weight),
+            Err(WeightError::NegativeWeight)
+        );
+        assert_eq!(
+            rng.choose_weighted(&[f64::NAN], |weight| *weight),
+            Err(WeightError::NonFiniteWeight)
+        );
+        assert_eq!(
+            rng.choose_weighted(&[0_u16, 0], |weight| *weight),
+            Err(WeightError::AllWeightsZero)
+        );
+    }
+}